@@ -0,0 +1,139 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{d00_aoc::Answer, records};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail(String),
+    Unknown,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail(expected) => write!(f, "FAIL, expected {}", expected),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Answers {
+    expected: HashMap<(u8, u8), String>,
+}
+
+impl Answers {
+    pub fn parse(input_str: &str) -> Result<Answers, Box<dyn Error>> {
+        let records = records::parse(input_str, "answers", |fields| {
+            let (day, part, answer) = match fields {
+                [day, part, answer] => (day.parse::<u8>(), part.parse::<u8>(), answer),
+                _ => return Err("expected 'day part answer'".to_string()),
+            };
+            match (day, part) {
+                (Ok(day), Ok(part)) if part == 1 || part == 2 => {
+                    Ok(((day, part), answer.to_string()))
+                }
+                _ => Err("invalid day or part".to_string()),
+            }
+        })?;
+        Ok(Answers {
+            expected: records.into_iter().collect(),
+        })
+    }
+
+    pub fn from_file(path: &Path) -> Result<Answers, Box<dyn Error>> {
+        let input_str = fs::read_to_string(path)?;
+        Self::parse(&input_str)
+    }
+
+    pub fn from_file_or_empty(path: &Path) -> Result<Answers, Box<dyn Error>> {
+        Self::parse(&records::read_or_empty(path)?)
+    }
+
+    // Adds a "day part answer" line, creating the file if needed.
+    pub fn append(path: &Path, day: u8, part: u8, answer: &str) -> io::Result<()> {
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if !content.is_empty() && !content.ends_with('\n') {
+            writeln!(file)?;
+        }
+        writeln!(file, "{:02} {} {}", day, part, answer)
+    }
+
+    pub fn expected(&self, day: u8, part: u8) -> Option<&str> {
+        self.expected.get(&(day, part)).map(|a| a.as_str())
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &Answer) -> Verdict {
+        match self.expected(day, part) {
+            Some(e) if e == answer.to_string() => Verdict::Pass,
+            Some(e) => Verdict::Fail(e.to_string()),
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::Path, process};
+
+    use crate::{
+        answers::{Answers, Verdict},
+        d00_aoc::Answer,
+    };
+
+    #[test]
+    fn test_parse_and_check() {
+        let answers =
+            Answers::parse("# day part answer\n01 1 1393\n1 2 1359\n\n04 1 abc\n").unwrap();
+        assert_eq!(answers.expected(1, 1), Some("1393"));
+        assert_eq!(answers.check(1, 1, &Answer::Integer(1393)), Verdict::Pass);
+        assert_eq!(
+            answers.check(1, 2, &Answer::Integer(1358)),
+            Verdict::Fail("1359".to_string())
+        );
+        assert_eq!(answers.check(2, 1, &Answer::Integer(1)), Verdict::Unknown);
+        assert_eq!(answers.check(4, 1, &Answer::from("abc")), Verdict::Pass);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Answers::parse("01 1").is_err());
+        assert!(Answers::parse("01 3 5").is_err());
+        assert!(Answers::parse("x 1 5").is_err());
+    }
+
+    #[test]
+    fn test_checked_in_answers() {
+        let answers = Answers::from_file(Path::new("inputs/2021/answers.txt")).unwrap();
+        assert_eq!(answers.expected(4, 1), Some("51776"));
+        assert_eq!(answers.expected(4, 2), Some("16830"));
+    }
+
+    #[test]
+    fn test_append() {
+        let path = env::temp_dir().join(format!("aoc-answers-{}", process::id()));
+        fs::write(&path, "01 1 1393").unwrap();
+        Answers::append(&path, 4, 2, "16830").unwrap();
+        let answers = Answers::from_file(&path).unwrap();
+        assert_eq!(answers.expected(1, 1), Some("1393"));
+        assert_eq!(answers.expected(4, 2), Some("16830"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let answers = Answers::from_file_or_empty(Path::new("inputs/missing.txt")).unwrap();
+        assert_eq!(answers.expected(1, 1), None);
+        assert!(Answers::from_file(Path::new("inputs/missing.txt")).is_err());
+    }
+}
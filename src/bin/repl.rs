@@ -0,0 +1,27 @@
+use std::io::{self, BufRead, Write};
+
+use advent_of_code_rust::repl::Session;
+
+fn main() {
+    let mut session = Session::default();
+    println!("Advent of Code REPL, type help for the commands");
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    while !session.done() {
+        print!("{}", session.prompt());
+        io::stdout().flush().unwrap();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(err)) => {
+                eprintln!("error: {}", err);
+                break;
+            }
+            None => break,
+        };
+        match session.handle(&line) {
+            Ok(out) if out.is_empty() => {}
+            Ok(out) => println!("{}", out),
+            Err(err) => eprintln!("error: {}", err),
+        }
+    }
+}
@@ -0,0 +1,1185 @@
+use std::{
+    error::Error,
+    ffi::OsString,
+    fmt,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    fetch,
+    registry::{self, Day},
+    report::Format,
+    submit,
+    trace::Level,
+};
+
+pub const USAGE: &str = "Usage: advent-of-code-rust [OPTIONS]
+       advent-of-code-rust new-day <DAY> <NAME> [--year <YYYY>] [--title <TITLE>]
+       advent-of-code-rust generate <DAY> [--year <YYYY>] [--size <N>] [--seed <N>]
+       advent-of-code-rust fetch <N|A-B> [--year <YYYY>] [--inputs-dir <DIR>]
+       advent-of-code-rust submit <DAY> <PART> [ANSWER] [--year <YYYY>]
+
+Options:
+  -y, --year <YYYY>     run that season (default the latest one)
+  -d, --day <N|A-B>     run a single day or an inclusive range of days
+  -p, --part <1|2>      run only one part
+  -i, --input <PATH>    read the input from PATH, or stdin for '-'
+                        (single day only)
+  -I, --inputs-dir <DIR>
+                        directory holding <YYYY>/dNN_input.txt and
+                        <YYYY>/answers.txt (default $AOC_INPUTS_DIR,
+                        then ./inputs)
+  -a, --answers <PATH>  expected answers to verify against
+                        (default <inputs-dir>/<YYYY>/answers.txt)
+  -f, --format <FMT>    report as table, json or csv (default table)
+  -t, --timeout <SECS>  report a day as timed out after SECS seconds
+  -j, --jobs <N>        run up to N days in parallel (0 = one per CPU)
+  -W, --watch           rerun the day whenever its input or answers file
+                        changes (single day only)
+  -r, --record          append this run's timings to the history file
+                        (not with --part or --input)
+  -c, --compare         compare timings against the history and flag
+                        days slower than the threshold (not with --part or
+                        --input)
+      --threshold <PCT> slowdown that counts as a regression (default 20)
+      --history <PATH>  timing history file (default ./.aoc-history)
+  -b, --bench <N>       time parse, part 1 and part 2 over N runs (not with
+                        --part)
+  -w, --warmup <N>      untimed runs before benchmarking (default 3)
+  -T, --trace <[[YYYY/]DAY=]LEVEL>
+                        print solver state at LEVEL (info or debug) to
+                        stderr, for every day or only DAY of the season
+                        run or of YYYY; repeatable
+  -h, --help            print this message";
+
+pub const NEW_DAY_USAGE: &str =
+    "Usage: advent-of-code-rust new-day <DAY> <NAME> [--year <YYYY>] [--title <TITLE>]
+
+Generates src/yYYYY/dNN_<NAME>.rs, registers it in its year module and the day
+registry, and creates empty inputs/YYYY/dNN_input.txt and
+src/test_inputs/YYYY/dNN_test_input.txt.
+
+Options:
+  -y, --year <YYYY>     season of the puzzle (default the latest one)
+  -t, --title <TITLE>   puzzle title (default NAME in title case)
+  -h, --help            print this message";
+
+pub const GENERATE_USAGE: &str =
+    "Usage: advent-of-code-rust generate <DAY> [--year <YYYY>] [--size <N>] [--seed <N>]
+
+Prints a random but valid input for the day, the same one for the same seed.
+
+Options:
+  -y, --year <YYYY>     season of the puzzle (default the latest one)
+  -s, --size <N>        lines, commands, boards or segments to generate
+                        (default about the size of the real input)
+      --seed <N>        random seed (default 1)
+  -h, --help            print this message";
+
+pub const FETCH_USAGE: &str =
+    "Usage: advent-of-code-rust fetch <N|A-B> [--year <YYYY>] [--inputs-dir <DIR>]
+
+Downloads the inputs of a day or an inclusive range of days into
+<inputs-dir>/<YYYY>/dNN_input.txt, a few seconds apart. An input already there
+is never downloaded again. The session cookie is read from $AOC_SESSION, then
+from the session file.
+
+Options:
+  -y, --year <YYYY>     season of the puzzles (default the latest one)
+  -I, --inputs-dir <DIR>
+                        where to save them (default $AOC_INPUTS_DIR, then
+                        ./inputs)
+      --session-file <PATH>
+                        file holding the session cookie (default
+                        ./.aoc-session)
+  -h, --help            print this message";
+
+pub const SUBMIT_USAGE: &str =
+    "Usage: advent-of-code-rust submit <DAY> <PART> [ANSWER] [--year <YYYY>]
+
+Sends an answer to the site, by default the one the day computes from its
+input, and records the verdict in the ledger. An answer already rejected, or
+outside the bounds left by earlier too high and too low verdicts, is refused
+without being sent. A right answer is added to the season's answers.txt.
+
+Options:
+  -y, --year <YYYY>     season of the puzzle (default the latest one)
+  -i, --input <PATH>    solve this input instead of the day's own
+  -I, --inputs-dir <DIR>
+                        directory holding <YYYY>/dNN_input.txt and
+                        <YYYY>/answers.txt (default $AOC_INPUTS_DIR,
+                        then ./inputs)
+      --session-file <PATH>
+                        file holding the session cookie (default
+                        ./.aoc-session)
+      --ledger <PATH>   answers sent so far and their verdicts (default
+                        ./.aoc-ledger)
+  -h, --help            print this message";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CliError {
+    MissingValue(String),
+    InvalidValue(String, String),
+    UnknownArgument(String),
+    UnknownDay(u16, u8),
+    UnknownYear(u16),
+    InputNeedsSingleDay,
+    WatchNeedsSingleDay,
+    WatchNeedsFile,
+    HistoryNeedsWholeDay(&'static str),
+    BenchNeedsBothParts,
+    MissingArgument(&'static str),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::MissingValue(arg) => write!(f, "missing value for {}", arg),
+            CliError::InvalidValue(arg, value) => {
+                write!(f, "invalid value '{}' for {}", value, arg)
+            }
+            CliError::UnknownArgument(arg) => write!(f, "unknown argument '{}'", arg),
+            CliError::UnknownDay(year, day) => {
+                write!(f, "day {} of {} is not implemented", day, year)
+            }
+            CliError::UnknownYear(year) => write!(f, "no day of {} is implemented", year),
+            CliError::InputNeedsSingleDay => write!(f, "--input requires a single --day"),
+            CliError::WatchNeedsSingleDay => write!(f, "--watch requires a single --day"),
+            CliError::WatchNeedsFile => write!(f, "--watch cannot watch stdin"),
+            CliError::HistoryNeedsWholeDay(arg) => {
+                write!(f, "--record and --compare cannot be used with {}", arg)
+            }
+            CliError::BenchNeedsBothParts => write!(f, "--bench cannot be used with --part"),
+            CliError::MissingArgument(arg) => write!(f, "missing argument <{}>", arg),
+        }
+    }
+}
+
+impl Error for CliError {}
+
+// `--inputs-dir` wins over the environment, which wins over ./inputs.
+pub fn inputs_dir(dir: &Option<PathBuf>, env_dir: Option<OsString>) -> PathBuf {
+    match (dir, env_dir) {
+        (Some(dir), _) => dir.clone(),
+        (None, Some(dir)) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from("./inputs"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaySelection {
+    All,
+    Range(u8, u8),
+}
+
+// A day picked by `--trace`, in the season being run unless given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceDay {
+    pub year: Option<u16>,
+    pub day: u8,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+    pub year: Option<u16>,
+    pub days: DaySelection,
+    pub part: Option<u8>,
+    pub input: Option<PathBuf>,
+    pub inputs_dir: Option<PathBuf>,
+    pub answers: Option<PathBuf>,
+    pub format: Format,
+    pub timeout: Option<Duration>,
+    pub jobs: usize,
+    pub watch: bool,
+    pub record: bool,
+    pub compare: bool,
+    pub threshold: u32,
+    pub history: Option<PathBuf>,
+    pub bench: Option<usize>,
+    pub warmup: usize,
+    pub trace: Vec<(Option<TraceDay>, Level)>,
+    pub help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            year: None,
+            days: DaySelection::All,
+            part: None,
+            input: None,
+            inputs_dir: None,
+            answers: None,
+            format: Format::Table,
+            timeout: None,
+            jobs: 1,
+            watch: false,
+            record: false,
+            compare: false,
+            threshold: 20,
+            history: None,
+            bench: None,
+            warmup: 3,
+            trace: vec![],
+            help: false,
+        }
+    }
+}
+
+impl Options {
+    pub fn parse(args: &[String]) -> Result<Options, CliError> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => options.help = true,
+                "-y" | "--year" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.year = Some(Self::parse_year(arg, value)?);
+                }
+                "-d" | "--day" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.days = Self::parse_days(arg, value)?;
+                }
+                "-p" | "--part" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.part = match value {
+                        "1" => Some(1),
+                        "2" => Some(2),
+                        _ => return Err(CliError::InvalidValue(arg.clone(), value.to_string())),
+                    };
+                }
+                "-i" | "--input" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.input = Some(PathBuf::from(value));
+                }
+                "-I" | "--inputs-dir" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.inputs_dir = Some(PathBuf::from(value));
+                }
+                "-a" | "--answers" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.answers = Some(PathBuf::from(value));
+                }
+                "-f" | "--format" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.format = value
+                        .parse()
+                        .map_err(|_| CliError::InvalidValue(arg.clone(), value.to_string()))?;
+                }
+                "-t" | "--timeout" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.timeout = match value.parse::<f64>() {
+                        Ok(secs) if secs > 0.0 && secs.is_finite() => {
+                            Some(Duration::from_secs_f64(secs))
+                        }
+                        _ => return Err(CliError::InvalidValue(arg.clone(), value.to_string())),
+                    };
+                }
+                "-j" | "--jobs" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.jobs = match value.parse() {
+                        Ok(0) => thread::available_parallelism().map_or(1, |n| n.get()),
+                        Ok(n) => n,
+                        Err(_) => {
+                            return Err(CliError::InvalidValue(arg.clone(), value.to_string()))
+                        }
+                    };
+                }
+                "-W" | "--watch" => options.watch = true,
+                "-r" | "--record" => options.record = true,
+                "-c" | "--compare" => options.compare = true,
+                "--threshold" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.threshold = value
+                        .parse()
+                        .map_err(|_| CliError::InvalidValue(arg.clone(), value.to_string()))?;
+                }
+                "--history" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.history = Some(PathBuf::from(value));
+                }
+                "-b" | "--bench" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.bench = match value.parse() {
+                        Ok(n) if n > 0 => Some(n),
+                        _ => return Err(CliError::InvalidValue(arg.clone(), value.to_string())),
+                    };
+                }
+                "-w" | "--warmup" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.warmup = value
+                        .parse()
+                        .map_err(|_| CliError::InvalidValue(arg.clone(), value.to_string()))?;
+                }
+                "-T" | "--trace" => {
+                    let value = Self::value(arg, args.next())?;
+                    options.trace.push(Self::parse_trace(arg, value)?);
+                }
+                _ => return Err(CliError::UnknownArgument(arg.clone())),
+            }
+        }
+        if options.input.is_some() && !options.is_single_day() {
+            return Err(CliError::InputNeedsSingleDay);
+        }
+        if options.watch && !options.is_single_day() {
+            return Err(CliError::WatchNeedsSingleDay);
+        }
+        if options.watch && options.input.as_deref() == Some(Path::new("-")) {
+            return Err(CliError::WatchNeedsFile);
+        }
+        // The history keeps one timing per day, of both parts on the day's
+        // own input; anything else would be compared against it as if it were.
+        if options.record || options.compare {
+            if options.part.is_some() {
+                return Err(CliError::HistoryNeedsWholeDay("--part"));
+            }
+            if options.input.is_some() {
+                return Err(CliError::HistoryNeedsWholeDay("--input"));
+            }
+        }
+        // A benchmark always times parse and both parts.
+        if options.bench.is_some() && options.part.is_some() {
+            return Err(CliError::BenchNeedsBothParts);
+        }
+        Ok(options)
+    }
+
+    // Without --year, the latest season in the registry.
+    pub fn year(&self, registry: &[Day]) -> u16 {
+        self.year
+            .or_else(|| registry.iter().map(|d| d.year).max())
+            .unwrap_or(2021)
+    }
+
+    // The days to enable tracing for, as (year, day), None for every day.
+    pub fn traced(&self, registry: &[Day]) -> Vec<(Option<(u16, u8)>, Level)> {
+        let year = self.year(registry);
+        self.trace
+            .iter()
+            .map(|&(day, level)| (day.map(|d| (d.year.unwrap_or(year), d.day)), level))
+            .collect()
+    }
+
+    pub fn select(&self, registry: &[Day]) -> Result<Vec<Day>, CliError> {
+        let year = self.year(registry);
+        let (first, last) = match self.days {
+            DaySelection::All => {
+                let days: Vec<Day> = registry
+                    .iter()
+                    .filter(|d| d.year == year)
+                    .copied()
+                    .collect();
+                if days.is_empty() {
+                    return Err(CliError::UnknownYear(year));
+                }
+                return Ok(days);
+            }
+            DaySelection::Range(first, last) => (first, last),
+        };
+        (first..=last)
+            .map(|n| {
+                registry
+                    .iter()
+                    .find(|d| d.year == year && d.day == n)
+                    .copied()
+                    .ok_or(CliError::UnknownDay(year, n))
+            })
+            .collect()
+    }
+
+    pub fn inputs_dir(&self, env_dir: Option<OsString>) -> PathBuf {
+        inputs_dir(&self.inputs_dir, env_dir)
+    }
+
+    pub fn history_path(&self) -> PathBuf {
+        self.history
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("./.aoc-history"))
+    }
+
+    pub fn input_path(&self, day: &Day, inputs_dir: &Path) -> PathBuf {
+        match &self.input {
+            Some(path) => path.clone(),
+            None => day.input_path(inputs_dir),
+        }
+    }
+
+    fn is_single_day(&self) -> bool {
+        matches!(self.days, DaySelection::Range(a, b) if a == b)
+    }
+
+    fn value<'a>(arg: &str, value: Option<&'a String>) -> Result<&'a str, CliError> {
+        match value {
+            Some(v) => Ok(v.as_str()),
+            None => Err(CliError::MissingValue(arg.to_string())),
+        }
+    }
+
+    fn parse_year(arg: &str, value: &str) -> Result<u16, CliError> {
+        match value.parse() {
+            Ok(year) if year >= 2015 => Ok(year),
+            _ => Err(CliError::InvalidValue(arg.to_string(), value.to_string())),
+        }
+    }
+
+    // "debug" for every day, "4=debug" for day 4 of the season being run,
+    // "2020/4=debug" for day 4 of 2020 only.
+    fn parse_trace(arg: &str, value: &str) -> Result<(Option<TraceDay>, Level), CliError> {
+        let invalid = || CliError::InvalidValue(arg.to_string(), value.to_string());
+        let (day, level) = match value.split_once('=') {
+            Some((day, level)) => {
+                let (year, day) = match day.split_once('/') {
+                    Some((year, day)) => (
+                        Some(Self::parse_year(arg, year).map_err(|_| invalid())?),
+                        day,
+                    ),
+                    None => (None, day),
+                };
+                match day.parse() {
+                    Ok(day) if (1..=25).contains(&day) => (Some(TraceDay { year, day }), level),
+                    _ => return Err(invalid()),
+                }
+            }
+            None => (None, value),
+        };
+        Ok((day, level.parse().map_err(|_| invalid())?))
+    }
+
+    fn parse_days(arg: &str, value: &str) -> Result<DaySelection, CliError> {
+        let invalid = || CliError::InvalidValue(arg.to_string(), value.to_string());
+        let day = |s: &str| -> Result<u8, CliError> {
+            match s.trim().parse() {
+                Ok(n) if (1..=25).contains(&n) => Ok(n),
+                _ => Err(invalid()),
+            }
+        };
+        match value.split_once('-') {
+            Some((a, b)) => {
+                let (a, b) = (day(a)?, day(b)?);
+                if a > b {
+                    return Err(invalid());
+                }
+                Ok(DaySelection::Range(a, b))
+            }
+            None => {
+                let a = day(value)?;
+                Ok(DaySelection::Range(a, a))
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NewDay {
+    pub year: u16,
+    pub day: u8,
+    pub name: String,
+    pub title: String,
+    pub help: bool,
+}
+
+impl NewDay {
+    pub fn parse(args: &[String]) -> Result<NewDay, CliError> {
+        let mut positional = vec![];
+        let mut title = None;
+        let mut year = None;
+        let mut help = false;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => help = true,
+                "-y" | "--year" => {
+                    year = Some(Options::parse_year(arg, Options::value(arg, args.next())?)?);
+                }
+                "-t" | "--title" => {
+                    title = Some(Options::value(arg, args.next())?.to_string());
+                }
+                a if a.starts_with('-') => return Err(CliError::UnknownArgument(arg.clone())),
+                _ => positional.push(arg),
+            }
+        }
+        if help {
+            return Ok(NewDay {
+                year: registry::latest_year(),
+                day: 0,
+                name: String::new(),
+                title: String::new(),
+                help,
+            });
+        }
+        let day = match positional.first() {
+            Some(value) => match value.parse() {
+                Ok(n) if (1..=25).contains(&n) => n,
+                _ => {
+                    return Err(CliError::InvalidValue(
+                        "<DAY>".to_string(),
+                        value.to_string(),
+                    ))
+                }
+            },
+            None => return Err(CliError::MissingArgument("DAY")),
+        };
+        let name = match positional.get(1) {
+            Some(value) if Self::is_valid_name(value) => value.to_string(),
+            Some(value) => {
+                return Err(CliError::InvalidValue(
+                    "<NAME>".to_string(),
+                    value.to_string(),
+                ))
+            }
+            None => return Err(CliError::MissingArgument("NAME")),
+        };
+        if let Some(extra) = positional.get(2) {
+            return Err(CliError::UnknownArgument(extra.to_string()));
+        }
+        let title = title.unwrap_or_else(|| Self::title_case(&name));
+        Ok(NewDay {
+            year: year.unwrap_or_else(registry::latest_year),
+            day,
+            name,
+            title,
+            help,
+        })
+    }
+
+    // The name becomes part of a module path, so keep it to snake case.
+    fn is_valid_name(name: &str) -> bool {
+        name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    }
+
+    fn title_case(name: &str) -> String {
+        name.split('_')
+            .filter(|w| !w.is_empty())
+            .map(|w| w[..1].to_uppercase() + &w[1..])
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct GenerateInput {
+    pub year: u16,
+    pub day: u8,
+    pub size: Option<usize>,
+    pub seed: u64,
+    pub help: bool,
+}
+
+impl GenerateInput {
+    pub fn parse(args: &[String]) -> Result<GenerateInput, CliError> {
+        let mut options = GenerateInput {
+            year: registry::latest_year(),
+            day: 0,
+            size: None,
+            seed: 1,
+            help: false,
+        };
+        let mut day = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => options.help = true,
+                "-y" | "--year" => {
+                    options.year = Options::parse_year(arg, Options::value(arg, args.next())?)?;
+                }
+                "-s" | "--size" => {
+                    let value = Options::value(arg, args.next())?;
+                    options.size = match value.parse() {
+                        Ok(n) if n > 0 => Some(n),
+                        _ => return Err(CliError::InvalidValue(arg.clone(), value.to_string())),
+                    };
+                }
+                "--seed" => {
+                    let value = Options::value(arg, args.next())?;
+                    options.seed = value
+                        .parse()
+                        .map_err(|_| CliError::InvalidValue(arg.clone(), value.to_string()))?;
+                }
+                a if a.starts_with('-') || day.is_some() => {
+                    return Err(CliError::UnknownArgument(arg.clone()))
+                }
+                _ => day = Some(arg),
+            }
+        }
+        if options.help {
+            return Ok(options);
+        }
+        options.day = match day {
+            Some(value) => match value.parse() {
+                Ok(n) if (1..=25).contains(&n) => n,
+                _ => {
+                    return Err(CliError::InvalidValue(
+                        "<DAY>".to_string(),
+                        value.to_string(),
+                    ))
+                }
+            },
+            None => return Err(CliError::MissingArgument("DAY")),
+        };
+        Ok(options)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FetchInputs {
+    pub year: u16,
+    pub days: (u8, u8),
+    pub inputs_dir: Option<PathBuf>,
+    pub session_file: PathBuf,
+    pub help: bool,
+}
+
+impl FetchInputs {
+    pub fn parse(args: &[String]) -> Result<FetchInputs, CliError> {
+        let mut options = FetchInputs {
+            year: registry::latest_year(),
+            days: (0, 0),
+            inputs_dir: None,
+            session_file: PathBuf::from(fetch::SESSION_FILE),
+            help: false,
+        };
+        let mut days = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => options.help = true,
+                "-y" | "--year" => {
+                    options.year = Options::parse_year(arg, Options::value(arg, args.next())?)?;
+                }
+                "-I" | "--inputs-dir" => {
+                    options.inputs_dir = Some(PathBuf::from(Options::value(arg, args.next())?));
+                }
+                "--session-file" => {
+                    options.session_file = PathBuf::from(Options::value(arg, args.next())?);
+                }
+                a if a.starts_with('-') || days.is_some() => {
+                    return Err(CliError::UnknownArgument(arg.clone()))
+                }
+                _ => days = Some(arg),
+            }
+        }
+        if options.help {
+            return Ok(options);
+        }
+        options.days = match days {
+            Some(value) => match Options::parse_days("<N|A-B>", value)? {
+                DaySelection::Range(a, b) => (a, b),
+                DaySelection::All => unreachable!(),
+            },
+            None => return Err(CliError::MissingArgument("N|A-B")),
+        };
+        Ok(options)
+    }
+
+    pub fn inputs_dir(&self, env_dir: Option<OsString>) -> PathBuf {
+        inputs_dir(&self.inputs_dir, env_dir)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SubmitAnswer {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub input: Option<PathBuf>,
+    pub inputs_dir: Option<PathBuf>,
+    pub session_file: PathBuf,
+    pub ledger: PathBuf,
+    pub help: bool,
+}
+
+impl SubmitAnswer {
+    pub fn parse(args: &[String]) -> Result<SubmitAnswer, CliError> {
+        let mut options = SubmitAnswer {
+            year: registry::latest_year(),
+            day: 0,
+            part: 0,
+            answer: None,
+            input: None,
+            inputs_dir: None,
+            session_file: PathBuf::from(fetch::SESSION_FILE),
+            ledger: PathBuf::from(submit::LEDGER_FILE),
+            help: false,
+        };
+        let mut positional = vec![];
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => options.help = true,
+                "-y" | "--year" => {
+                    options.year = Options::parse_year(arg, Options::value(arg, args.next())?)?;
+                }
+                "-i" | "--input" => {
+                    options.input = Some(PathBuf::from(Options::value(arg, args.next())?));
+                }
+                "-I" | "--inputs-dir" => {
+                    options.inputs_dir = Some(PathBuf::from(Options::value(arg, args.next())?));
+                }
+                "--session-file" => {
+                    options.session_file = PathBuf::from(Options::value(arg, args.next())?);
+                }
+                "--ledger" => options.ledger = PathBuf::from(Options::value(arg, args.next())?),
+                a if a.starts_with('-') && a.parse::<i64>().is_err() || positional.len() == 3 => {
+                    return Err(CliError::UnknownArgument(arg.clone()))
+                }
+                _ => positional.push(arg),
+            }
+        }
+        if options.help {
+            return Ok(options);
+        }
+        let invalid =
+            |name: &str, value: &str| CliError::InvalidValue(name.to_string(), value.to_string());
+        options.day = match positional.first() {
+            Some(value) => match value.parse() {
+                Ok(n) if (1..=25).contains(&n) => n,
+                _ => return Err(invalid("<DAY>", value)),
+            },
+            None => return Err(CliError::MissingArgument("DAY")),
+        };
+        options.part = match positional.get(1) {
+            Some(value) => match value.parse() {
+                Ok(n) if n == 1 || n == 2 => n,
+                _ => return Err(invalid("<PART>", value)),
+            },
+            None => return Err(CliError::MissingArgument("PART")),
+        };
+        options.answer = positional.get(2).map(|a| a.to_string());
+        Ok(options)
+    }
+
+    pub fn inputs_dir(&self, env_dir: Option<OsString>) -> PathBuf {
+        inputs_dir(&self.inputs_dir, env_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::OsString,
+        path::{Path, PathBuf},
+        time::Duration,
+    };
+
+    use crate::{
+        cli::{
+            CliError, DaySelection, FetchInputs, GenerateInput, NewDay, Options, SubmitAnswer,
+            TraceDay,
+        },
+        registry,
+        report::Format,
+        trace::Level,
+    };
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let options = Options::parse(&[]).unwrap();
+        assert_eq!(options, Options::default());
+        assert_eq!(options.days, DaySelection::All);
+    }
+
+    #[test]
+    fn test_parse_day_and_part() {
+        let options =
+            Options::parse(&args("--day 4 --part 2 --input foo.txt --answers bar.txt")).unwrap();
+        assert_eq!(options.days, DaySelection::Range(4, 4));
+        assert_eq!(options.part, Some(2));
+        assert_eq!(options.input, Some(PathBuf::from("foo.txt")));
+        assert_eq!(options.answers, Some(PathBuf::from("bar.txt")));
+    }
+
+    #[test]
+    fn test_parse_range() {
+        let options = Options::parse(&args("-d 2-4")).unwrap();
+        assert_eq!(options.days, DaySelection::Range(2, 4));
+    }
+
+    #[test]
+    fn test_inputs_dir() {
+        let options = Options::parse(&[]).unwrap();
+        assert_eq!(options.inputs_dir(None), PathBuf::from("./inputs"));
+        assert_eq!(
+            options.inputs_dir(Some(OsString::from("/data/aoc"))),
+            PathBuf::from("/data/aoc")
+        );
+        assert_eq!(
+            options.inputs_dir(Some(OsString::new())),
+            PathBuf::from("./inputs")
+        );
+
+        let options = Options::parse(&args("--inputs-dir other")).unwrap();
+        assert_eq!(
+            options.inputs_dir(Some(OsString::from("/data/aoc"))),
+            PathBuf::from("other")
+        );
+        let day = registry::find(2021, 2).unwrap();
+        assert_eq!(
+            options.input_path(&day, Path::new("other")),
+            PathBuf::from("other/2021/d02_input.txt")
+        );
+    }
+
+    #[test]
+    fn test_input_stdin() {
+        let options = Options::parse(&args("--day 2 --input -")).unwrap();
+        let day = registry::find(2021, 2).unwrap();
+        assert_eq!(
+            options.input_path(&day, Path::new("other")),
+            PathBuf::from("-")
+        );
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let options = Options::parse(&args("--format json")).unwrap();
+        assert_eq!(options.format, Format::Json);
+        assert_eq!(
+            Options::parse(&args("-f xml")),
+            Err(CliError::InvalidValue("-f".to_string(), "xml".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        let options = Options::parse(&args("--timeout 1.5")).unwrap();
+        assert_eq!(options.timeout, Some(Duration::from_millis(1500)));
+        assert_eq!(
+            Options::parse(&args("--timeout -1")),
+            Err(CliError::InvalidValue(
+                "--timeout".to_string(),
+                "-1".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_jobs() {
+        assert_eq!(Options::parse(&args("--jobs 4")).unwrap().jobs, 4);
+        assert!(Options::parse(&args("-j 0")).unwrap().jobs >= 1);
+        assert_eq!(
+            Options::parse(&args("-j x")),
+            Err(CliError::InvalidValue("-j".to_string(), "x".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_watch() {
+        let options = Options::parse(&args("--day 4 --watch")).unwrap();
+        assert!(options.watch);
+        assert_eq!(
+            Options::parse(&args("--watch")),
+            Err(CliError::WatchNeedsSingleDay)
+        );
+        assert_eq!(
+            Options::parse(&args("-d 4 -W -i -")),
+            Err(CliError::WatchNeedsFile)
+        );
+    }
+
+    #[test]
+    fn test_parse_history() {
+        let options = Options::parse(&[]).unwrap();
+        assert!(!options.record && !options.compare);
+        assert_eq!(options.history_path(), PathBuf::from("./.aoc-history"));
+
+        let options =
+            Options::parse(&args("--record -c --threshold 50 --history runs.txt")).unwrap();
+        assert!(options.record && options.compare);
+        assert_eq!(options.threshold, 50);
+        assert_eq!(options.history_path(), PathBuf::from("runs.txt"));
+        assert_eq!(
+            Options::parse(&args("--threshold -5")),
+            Err(CliError::InvalidValue(
+                "--threshold".to_string(),
+                "-5".to_string()
+            ))
+        );
+        assert_eq!(
+            Options::parse(&args("--record -d 4 -p 1")),
+            Err(CliError::HistoryNeedsWholeDay("--part"))
+        );
+        assert_eq!(
+            Options::parse(&args("-c -d 4 -i -"))
+                .unwrap_err()
+                .to_string(),
+            "--record and --compare cannot be used with --input"
+        );
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        let options = Options::parse(&args("--bench 100 --warmup 10")).unwrap();
+        assert_eq!(options.bench, Some(100));
+        assert_eq!(options.warmup, 10);
+        assert_eq!(
+            Options::parse(&args("--bench 0")),
+            Err(CliError::InvalidValue(
+                "--bench".to_string(),
+                "0".to_string()
+            ))
+        );
+        assert_eq!(
+            Options::parse(&args("-d 4 -p 2 --bench 10"))
+                .unwrap_err()
+                .to_string(),
+            "--bench cannot be used with --part"
+        );
+    }
+
+    #[test]
+    fn test_parse_trace() {
+        let options = Options::parse(&args("--trace info -T 4=debug -T 2020/3=info")).unwrap();
+        let day = |year, day| Some(TraceDay { year, day });
+        assert_eq!(
+            options.trace,
+            vec![
+                (None, Level::Info),
+                (day(None, 4), Level::Debug),
+                (day(Some(2020), 3), Level::Info)
+            ]
+        );
+        let days = registry::days();
+        let latest = registry::latest_year();
+        assert_eq!(
+            options.traced(&days),
+            vec![
+                (None, Level::Info),
+                (Some((latest, 4)), Level::Debug),
+                (Some((2020, 3)), Level::Info)
+            ]
+        );
+        let options = Options::parse(&args("-y 2021 -T 4=debug")).unwrap();
+        assert_eq!(options.traced(&days), vec![(Some((2021, 4)), Level::Debug)]);
+        for value in [
+            "verbose",
+            "4=",
+            "26=info",
+            "x=debug",
+            "4",
+            "2020/=info",
+            "1999/4=info",
+            "2020/4",
+        ] {
+            assert_eq!(
+                Options::parse(&args(&format!("-T {}", value))),
+                Err(CliError::InvalidValue("-T".to_string(), value.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Options::parse(&args("--day")),
+            Err(CliError::MissingValue("--day".to_string()))
+        );
+        assert_eq!(
+            Options::parse(&args("--day 4-2")),
+            Err(CliError::InvalidValue(
+                "--day".to_string(),
+                "4-2".to_string()
+            ))
+        );
+        assert_eq!(
+            Options::parse(&args("--part 3")),
+            Err(CliError::InvalidValue(
+                "--part".to_string(),
+                "3".to_string()
+            ))
+        );
+        assert_eq!(
+            Options::parse(&args("--days 1")),
+            Err(CliError::UnknownArgument("--days".to_string()))
+        );
+        assert_eq!(
+            Options::parse(&args("--day 1-2 --input foo.txt")),
+            Err(CliError::InputNeedsSingleDay)
+        );
+    }
+
+    #[test]
+    fn test_select() {
+        let days = registry::days();
+        let all = Options::parse(&[]).unwrap().select(&days).unwrap();
+        let latest = registry::latest_year();
+        assert_eq!(all.len(), days.iter().filter(|d| d.year == latest).count());
+
+        let some = Options::parse(&args("--year 2021 --day 2-3")).unwrap();
+        let some = some.select(&days).unwrap();
+        assert_eq!(some.iter().map(|d| d.day).collect::<Vec<u8>>(), vec![2, 3]);
+
+        let unknown = Options::parse(&args("--year 2021 --day 25")).unwrap();
+        assert_eq!(
+            unknown.select(&days).err(),
+            Some(CliError::UnknownDay(2021, 25))
+        );
+    }
+
+    #[test]
+    fn test_year() {
+        let days = registry::days();
+        let options = Options::parse(&args("--year 2021")).unwrap();
+        assert_eq!(options.year, Some(2021));
+        assert!(options
+            .select(&days)
+            .unwrap()
+            .iter()
+            .all(|d| d.year == 2021));
+        assert_eq!(
+            Options::parse(&[]).unwrap().year(&days),
+            registry::latest_year()
+        );
+
+        let options = Options::parse(&args("-y 2016")).unwrap();
+        assert_eq!(
+            options.select(&days).err(),
+            Some(CliError::UnknownYear(2016))
+        );
+        assert_eq!(
+            Options::parse(&args("--year 1999")),
+            Err(CliError::InvalidValue(
+                "--year".to_string(),
+                "1999".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_new_day() {
+        let new_day = NewDay::parse(&args("6 lanternfish")).unwrap();
+        assert_eq!(new_day.day, 6);
+        assert_eq!(new_day.name, "lanternfish");
+        assert_eq!(new_day.title, "Lanternfish");
+        assert_eq!(new_day.year, registry::latest_year());
+        assert_eq!(NewDay::parse(&args("1 x --year 2022")).unwrap().year, 2022);
+
+        let new_day = NewDay::parse(&args("7 treachery_of_whales")).unwrap();
+        assert_eq!(new_day.title, "Treachery Of Whales");
+
+        let mut a = args("7 whales --title");
+        a.push("The Treachery of Whales".to_string());
+        assert_eq!(NewDay::parse(&a).unwrap().title, "The Treachery of Whales");
+        assert!(NewDay::parse(&args("--help")).unwrap().help);
+    }
+
+    #[test]
+    fn test_new_day_errors() {
+        assert_eq!(NewDay::parse(&[]), Err(CliError::MissingArgument("DAY")));
+        assert_eq!(
+            NewDay::parse(&args("6")),
+            Err(CliError::MissingArgument("NAME"))
+        );
+        assert_eq!(
+            NewDay::parse(&args("26 x")),
+            Err(CliError::InvalidValue(
+                "<DAY>".to_string(),
+                "26".to_string()
+            ))
+        );
+        assert_eq!(
+            NewDay::parse(&args("6 Lantern-Fish")),
+            Err(CliError::InvalidValue(
+                "<NAME>".to_string(),
+                "Lantern-Fish".to_string()
+            ))
+        );
+        assert_eq!(
+            NewDay::parse(&args("6 fish extra")),
+            Err(CliError::UnknownArgument("extra".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_generate_input() {
+        let options = GenerateInput::parse(&args("5")).unwrap();
+        assert_eq!(options.day, 5);
+        assert_eq!(options.size, None);
+        assert_eq!(options.seed, 1);
+
+        let options = GenerateInput::parse(&args("4 --size 1000 --seed 42 -y 2021")).unwrap();
+        assert_eq!(options.size, Some(1000));
+        assert_eq!(options.seed, 42);
+        assert_eq!(options.year, 2021);
+
+        assert_eq!(
+            GenerateInput::parse(&[]),
+            Err(CliError::MissingArgument("DAY"))
+        );
+        assert_eq!(
+            GenerateInput::parse(&args("4 --size 0")),
+            Err(CliError::InvalidValue(
+                "--size".to_string(),
+                "0".to_string()
+            ))
+        );
+        assert_eq!(
+            GenerateInput::parse(&args("4 5")),
+            Err(CliError::UnknownArgument("5".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_fetch_inputs() {
+        let options = FetchInputs::parse(&args("4")).unwrap();
+        assert_eq!(options.days, (4, 4));
+        assert_eq!(options.session_file, PathBuf::from(".aoc-session"));
+        assert_eq!(options.inputs_dir(None), PathBuf::from("./inputs"));
+
+        let options = FetchInputs::parse(&args("1-5 -y 2020 -I in --session-file cookie")).unwrap();
+        assert_eq!((options.year, options.days), (2020, (1, 5)));
+        assert_eq!(options.inputs_dir(Some("env".into())), PathBuf::from("in"));
+        assert_eq!(options.session_file, PathBuf::from("cookie"));
+
+        assert_eq!(
+            FetchInputs::parse(&[]),
+            Err(CliError::MissingArgument("N|A-B"))
+        );
+        assert_eq!(
+            FetchInputs::parse(&args("5-1")),
+            Err(CliError::InvalidValue(
+                "<N|A-B>".to_string(),
+                "5-1".to_string()
+            ))
+        );
+        assert!(FetchInputs::parse(&args("--help")).unwrap().help);
+    }
+
+    #[test]
+    fn test_submit_answer() {
+        let options = SubmitAnswer::parse(&args("4 2")).unwrap();
+        assert_eq!((options.day, options.part, options.answer), (4, 2, None));
+        assert_eq!(options.ledger, PathBuf::from(".aoc-ledger"));
+
+        let options = SubmitAnswer::parse(&args("4 1 -12 --ledger l -i in.txt")).unwrap();
+        assert_eq!(options.answer, Some("-12".to_string()));
+        assert_eq!(options.ledger, PathBuf::from("l"));
+        assert_eq!(options.input, Some(PathBuf::from("in.txt")));
+
+        assert_eq!(
+            SubmitAnswer::parse(&args("4")),
+            Err(CliError::MissingArgument("PART"))
+        );
+        assert_eq!(
+            SubmitAnswer::parse(&args("4 3")),
+            Err(CliError::InvalidValue(
+                "<PART>".to_string(),
+                "3".to_string()
+            ))
+        );
+        assert_eq!(
+            SubmitAnswer::parse(&args("4 1 12 13")),
+            Err(CliError::UnknownArgument("13".to_string()))
+        );
+    }
+}
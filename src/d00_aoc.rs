@@ -1,8 +1,233 @@
-use std::{error::Error, path::Path};
+use std::{
+    error::Error,
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: &'static str,
+}
+
+impl ParseError {
+    // `token` must be a subslice of `line`, so its column can be recovered
+    // from the pointer offset.
+    pub fn at(
+        day: u8,
+        line_number: usize,
+        line: &str,
+        token: &str,
+        expected: &'static str,
+    ) -> Self {
+        let offset = (token.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+        ParseError {
+            day,
+            line: line_number,
+            column: offset.min(line.len()) + 1,
+            text: token.to_string(),
+            expected,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {} input, line {}, column {}: expected {}, found '{}'",
+            self.day, self.line, self.column, self.expected, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
 
 pub trait InputReader<T> {
-    fn string_to_vector(input_str: String) -> Vec<T>;
+    fn string_to_vector(input_str: String) -> Result<Vec<T>, ParseError>;
+    fn from_reader<R: BufRead>(reader: R) -> Result<Self, Box<dyn Error>>
+    where
+        Self: Sized;
+
     fn from_file(input_filepath: &Path) -> Result<Self, Box<dyn Error>>
+    where
+        Self: Sized,
+    {
+        let file = File::open(input_filepath)?;
+        Self::from_reader(BufReader::new(file))
+    }
+}
+
+// Calls `f` with the 1-based number and the content of every line, without the
+// line ending, reusing a single buffer for the whole input.
+pub fn for_each_line<R: BufRead>(
+    mut reader: R,
+    mut f: impl FnMut(usize, &str) -> Result<(), ParseError>,
+) -> Result<(), Box<dyn Error>> {
+    let mut buffer = String::new();
+    let mut line_number = 0;
+    loop {
+        buffer.clear();
+        if reader.read_line(&mut buffer)? == 0 {
+            return Ok(());
+        }
+        line_number += 1;
+        let line = buffer.strip_suffix('\n').unwrap_or(&buffer);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        f(line_number, line)?;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Integer(n as i64)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Self {
+        Answer::Integer(n as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Integer(n)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Integer(n as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+pub trait Solution {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>>
     where
         Self: Sized;
+    fn part1(&self) -> Answer;
+    fn part2(&self) -> Answer;
+
+    // Day-specific commands for the REPL, as usage and description pairs.
+    fn queries(&self) -> Vec<(&'static str, &'static str)> {
+        vec![]
+    }
+
+    // None when `name` is not one of `queries`.
+    fn query(&self, name: &str, args: &[&str]) -> Option<Result<String, Box<dyn Error>>> {
+        let _ = (name, args);
+        None
+    }
+}
+
+// The argument of a query at `index`, parsed.
+pub fn query_arg<T: FromStr>(args: &[&str], index: usize, name: &str) -> Result<T, Box<dyn Error>> {
+    let arg = args
+        .get(index)
+        .ok_or_else(|| format!("missing argument <{}>", name))?;
+    arg.parse()
+        .map_err(|_| format!("invalid value '{}' for <{}>", arg, name).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::d00_aoc::{for_each_line, Answer, ParseError};
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(1393).to_string(), "1393");
+        assert_eq!(Answer::from(4245351_u32).to_string(), "4245351");
+        assert_eq!(Answer::from("abc").to_string(), "abc");
+    }
+
+    #[test]
+    fn test_answer_from() {
+        assert_eq!(Answer::from(7_usize), Answer::Integer(7));
+        assert_eq!(Answer::from(-3), Answer::Integer(-3));
+        assert_eq!(
+            Answer::from(String::from("x")),
+            Answer::Text("x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_error_column() {
+        let line = "forward x5";
+        let err = ParseError::at(2, 7, line, &line[8..], "an integer");
+        assert_eq!(err.line, 7);
+        assert_eq!(err.column, 9);
+        assert_eq!(err.text, "x5");
+        assert_eq!(
+            err.to_string(),
+            "day 2 input, line 7, column 9: expected an integer, found 'x5'"
+        );
+    }
+
+    #[test]
+    fn test_for_each_line() {
+        let mut lines = vec![];
+        for_each_line("a\r\nb\n\nc".as_bytes(), |n, line| {
+            lines.push((n, line.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                (1, "a".to_string()),
+                (2, "b".to_string()),
+                (3, "".to_string()),
+                (4, "c".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_for_each_line_error() {
+        let err = for_each_line("a\nb".as_bytes(), |n, line| {
+            if n == 2 {
+                return Err(ParseError::at(1, n, line, line, "nothing"));
+            }
+            Ok(())
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 1 input, line 2, column 1: expected nothing, found 'b'"
+        );
+    }
 }
@@ -0,0 +1,577 @@
+use std::{
+    error::Error,
+    ffi::OsString,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::registry;
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+// The site asks automated tools to identify themselves.
+pub const USER_AGENT: &str = "advent-of-code-rust input fetcher";
+
+// Requests for inputs are at least this far apart, across runs too.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(3);
+
+// When the last input was requested, kept in the inputs directory (ignored by
+// git).
+pub const STAMP_FILE: &str = ".last-request";
+
+// A request taking longer fails, rather than stalling a whole `fetch 1-25`.
+pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+// Where the session cookie lives when $AOC_SESSION is not set; keep it out of
+// version control.
+pub const SESSION_FILE: &str = ".aoc-session";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+// The transport under `Fetcher` and the answer submitter: `Curl` talks to the
+// real site, `FakeHttp` answers from memory in tests.
+pub trait Http {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<Response, Box<dyn Error>>;
+
+    // `form` is already URL-encoded, "level=1&answer=7".
+    fn post(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+        form: &str,
+    ) -> Result<Response, Box<dyn Error>>;
+}
+
+// Runs the curl binary, so the crate needs no TLS stack of its own. Headers go
+// through stdin to keep the session cookie out of the process list.
+pub struct Curl;
+
+impl Curl {
+    fn run(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+        form: Option<&str>,
+    ) -> Result<Response, Box<dyn Error>> {
+        let mut command = Command::new("curl");
+        command.args(["--silent", "--show-error", "--header", "@-"]);
+        command.args(["--max-time", &TIMEOUT.as_secs().to_string()]);
+        if let Some(form) = form {
+            command.args(["--data-raw", form]);
+        }
+        let mut child = command
+            .args(["--write-out", "\n%{http_code}", url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("cannot run curl: {}", e))?;
+        {
+            let mut stdin = child.stdin.take().unwrap();
+            for (name, value) in headers {
+                writeln!(stdin, "{}: {}", name, value)?;
+            }
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "curl failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        let stdout = String::from_utf8(output.stdout)?;
+        let (body, status) = stdout
+            .rsplit_once('\n')
+            .ok_or("curl printed no status code")?;
+        Ok(Response {
+            status: status
+                .parse()
+                .map_err(|_| format!("curl printed an invalid status code '{}'", status))?,
+            body: body.to_string(),
+        })
+    }
+}
+
+impl Http for Curl {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<Response, Box<dyn Error>> {
+        self.run(url, headers, None)
+    }
+
+    fn post(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+        form: &str,
+    ) -> Result<Response, Box<dyn Error>> {
+        self.run(url, headers, Some(form))
+    }
+}
+
+// Spaces requests out by `interval`. With a stamp file the time of the last
+// request outlives the process, so back to back runs are spaced out as well.
+pub struct RateLimiter {
+    interval: Duration,
+    last: Option<SystemTime>,
+    stamp: Option<PathBuf>,
+}
+
+impl RateLimiter {
+    pub fn new(interval: Duration) -> RateLimiter {
+        RateLimiter {
+            interval,
+            last: None,
+            stamp: None,
+        }
+    }
+
+    pub fn stamp(mut self, path: &Path) -> RateLimiter {
+        self.stamp = Some(path.to_path_buf());
+        self
+    }
+
+    // Milliseconds since the epoch, as the stamp file holds them.
+    fn stamped(&self) -> Option<SystemTime> {
+        let millis = fs::read_to_string(self.stamp.as_ref()?).ok()?;
+        let millis = millis.trim().parse().ok()?;
+        Some(UNIX_EPOCH + Duration::from_millis(millis))
+    }
+
+    // Sleeps until `interval` has passed since the previous request, this
+    // process's or the one in the stamp file, and returns how long it slept.
+    pub fn wait(&mut self) -> Duration {
+        let last = self.last.max(self.stamped());
+        let slept = match last {
+            Some(last) => {
+                // A request stamped in the future counts as just made.
+                let elapsed = last.elapsed().unwrap_or(Duration::ZERO);
+                let wait = self.interval.saturating_sub(elapsed);
+                thread::sleep(wait);
+                wait
+            }
+            None => Duration::ZERO,
+        };
+        let now = SystemTime::now();
+        self.last = Some(now);
+        if let Some(stamp) = &self.stamp {
+            // Best effort: without the stamp only this process is throttled.
+            let millis = now
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            let _ = stamp
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| fs::write(stamp, millis.to_string()));
+        }
+        slept
+    }
+}
+
+// What went wrong when the site answers `url` with a status that neither the
+// fetcher nor the submitter expects.
+pub fn status_error(url: &str, status: u16) -> Box<dyn Error> {
+    match status {
+        400 | 500 => format!(
+            "the session cookie was rejected (status {}), it may have expired",
+            status
+        )
+        .into(),
+        _ => format!("unexpected status {} from {}", status, url).into(),
+    }
+}
+
+// $AOC_SESSION wins over the session file. Either may hold the bare cookie
+// value or "session=<value>".
+pub fn session(env_session: Option<OsString>, path: &Path) -> Result<String, Box<dyn Error>> {
+    let session = match env_session {
+        Some(session) if !session.is_empty() => session
+            .into_string()
+            .map_err(|_| "$AOC_SESSION is not valid UTF-8")?,
+        _ => fs::read_to_string(path).map_err(|e| {
+            format!(
+                "no session cookie: set $AOC_SESSION or write it to {} ({})",
+                path.display(),
+                e
+            )
+        })?,
+    };
+    let session = session.trim();
+    let session = session.strip_prefix("session=").unwrap_or(session);
+    if session.is_empty() || !session.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("the session cookie must be the cookie's letters and digits".into());
+    }
+    Ok(session.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fetched {
+    pub path: PathBuf,
+    pub cached: bool,
+}
+
+// Downloads inputs into <inputs-dir>/<YYYY>/dNN_input.txt. A day whose file
+// is already there, and not empty like the one `new-day` creates, is never
+// requested again.
+pub struct Fetcher<H: Http> {
+    http: H,
+    session: String,
+    inputs_dir: PathBuf,
+    base_url: String,
+    limiter: RateLimiter,
+}
+
+impl<H: Http> Fetcher<H> {
+    pub fn new(http: H, session: &str, inputs_dir: &Path) -> Fetcher<H> {
+        Fetcher {
+            http,
+            session: session.to_string(),
+            inputs_dir: inputs_dir.to_path_buf(),
+            base_url: BASE_URL.to_string(),
+            limiter: RateLimiter::new(MIN_INTERVAL).stamp(&inputs_dir.join(STAMP_FILE)),
+        }
+    }
+
+    pub fn base_url(mut self, base_url: &str) -> Fetcher<H> {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn interval(mut self, interval: Duration) -> Fetcher<H> {
+        self.limiter.interval = interval;
+        self
+    }
+
+    pub fn http(&self) -> &H {
+        &self.http
+    }
+
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        registry::year_dir(&self.inputs_dir, year).join(format!("d{:02}_input.txt", day))
+    }
+
+    pub fn fetch(&mut self, year: u16, day: u8) -> Result<Fetched, Box<dyn Error>> {
+        let path = self.path(year, day);
+        if fs::metadata(&path).is_ok_and(|m| m.len() > 0) {
+            return Ok(Fetched { path, cached: true });
+        }
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let cookie = format!("session={}", self.session);
+        self.limiter.wait();
+        let response = self
+            .http
+            .get(&url, &[("Cookie", &cookie), ("User-Agent", USER_AGENT)])?;
+        match response.status {
+            200 if !response.body.is_empty() => {}
+            200 => return Err(format!("{} returned an empty input", url).into()),
+            404 => {
+                return Err(format!(
+                    "day {} of {} is not unlocked yet, or does not exist",
+                    day, year
+                )
+                .into())
+            }
+            status => return Err(status_error(&url, status)),
+        }
+        // Written aside and renamed, so an interrupted write is never taken
+        // for a cached input.
+        fs::create_dir_all(path.parent().unwrap())?;
+        let partial = path.with_extension("txt.part");
+        fs::write(&partial, &response.body)?;
+        fs::rename(&partial, &path)?;
+        Ok(Fetched {
+            path,
+            cached: false,
+        })
+    }
+}
+
+#[cfg(test)]
+pub use fake::{FakeHttp, Request};
+
+// Test doubles, kept out of the library's API.
+#[cfg(test)]
+mod fake {
+    use std::{cell::RefCell, collections::HashMap, error::Error};
+
+    use crate::fetch::{Http, Response};
+
+    // A request as `FakeHttp` saw it; `form` is None for a GET.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Request {
+        pub url: String,
+        pub headers: Vec<(String, String)>,
+        pub form: Option<String>,
+    }
+
+    // Canned responses by URL, whatever the method, and a log of every request
+    // made; any other URL gets a 404.
+    #[derive(Default)]
+    pub struct FakeHttp {
+        responses: HashMap<String, Response>,
+        requests: RefCell<Vec<Request>>,
+    }
+
+    impl FakeHttp {
+        pub fn respond(&mut self, url: &str, status: u16, body: &str) {
+            let response = Response {
+                status,
+                body: body.to_string(),
+            };
+            self.responses.insert(url.to_string(), response);
+        }
+
+        // Oldest first.
+        pub fn requests(&self) -> Vec<Request> {
+            self.requests.borrow().clone()
+        }
+
+        fn answer(&self, url: &str, headers: &[(&str, &str)], form: Option<&str>) -> Response {
+            let headers = headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            self.requests.borrow_mut().push(Request {
+                url: url.to_string(),
+                headers,
+                form: form.map(|f| f.to_string()),
+            });
+            self.responses.get(url).cloned().unwrap_or(Response {
+                status: 404,
+                body: "404 Not Found".to_string(),
+            })
+        }
+    }
+
+    impl Http for FakeHttp {
+        fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<Response, Box<dyn Error>> {
+            Ok(self.answer(url, headers, None))
+        }
+
+        fn post(
+            &self,
+            url: &str,
+            headers: &[(&str, &str)],
+            form: &str,
+        ) -> Result<Response, Box<dyn Error>> {
+            Ok(self.answer(url, headers, Some(form)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        path::PathBuf,
+        process::{self, Command},
+        thread::{self, JoinHandle},
+        time::{Duration, Instant},
+    };
+
+    use crate::fetch::{self, Curl, FakeHttp, Fetcher, Http, RateLimiter};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    const URL: &str = "https://adventofcode.com/2021/day/1/input";
+
+    #[test]
+    fn test_fetch_and_cache() {
+        let dir = temp_dir("cache");
+        let mut http = FakeHttp::default();
+        http.respond(URL, 200, "199\n200\n");
+        let mut fetcher = Fetcher::new(http, "abc123", &dir).interval(Duration::ZERO);
+
+        let fetched = fetcher.fetch(2021, 1).unwrap();
+        assert!(!fetched.cached);
+        assert_eq!(fetched.path, dir.join("2021").join("d01_input.txt"));
+        assert_eq!(fs::read_to_string(&fetched.path).unwrap(), "199\n200\n");
+
+        let fetched = fetcher.fetch(2021, 1).unwrap();
+        assert!(fetched.cached);
+        let requests = fetcher.http().requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url, URL);
+        assert!(requests[0]
+            .headers
+            .contains(&("Cookie".to_string(), "session=abc123".to_string())));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_replaces_empty_input() {
+        let dir = temp_dir("empty");
+        fs::create_dir_all(dir.join("2021")).unwrap();
+        fs::write(dir.join("2021").join("d01_input.txt"), "").unwrap();
+        let mut http = FakeHttp::default();
+        http.respond(URL, 200, "199\n");
+        let mut fetcher = Fetcher::new(http, "abc123", &dir).interval(Duration::ZERO);
+        assert!(!fetcher.fetch(2021, 1).unwrap().cached);
+        assert_eq!(fetcher.http().requests().len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let dir = temp_dir("errors");
+        let mut http = FakeHttp::default();
+        http.respond(
+            URL,
+            400,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+        );
+        let mut fetcher = Fetcher::new(http, "abc123", &dir).interval(Duration::ZERO);
+        let err = fetcher.fetch(2021, 1).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the session cookie was rejected (status 400), it may have expired"
+        );
+        let err = fetcher.fetch(2021, 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 2 of 2021 is not unlocked yet, or does not exist"
+        );
+        assert!(!dir.join("2021").exists());
+        assert_eq!(
+            fetch::status_error(URL, 503).to_string(),
+            format!("unexpected status 503 from {}", URL)
+        );
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut limiter = RateLimiter::new(Duration::from_millis(30));
+        let now = Instant::now();
+        assert_eq!(limiter.wait(), Duration::ZERO);
+        assert!(limiter.wait() > Duration::ZERO);
+        assert!(now.elapsed() >= Duration::from_millis(30));
+
+        let dir = temp_dir("limit");
+        let mut fetcher =
+            Fetcher::new(FakeHttp::default(), "abc123", &dir).interval(Duration::from_millis(30));
+        let now = Instant::now();
+        for day in 1..=3 {
+            assert!(fetcher.fetch(2021, day).is_err());
+        }
+        assert!(now.elapsed() >= Duration::from_millis(60));
+
+        // A second run against the same inputs directory waits for the first.
+        let mut fetcher =
+            Fetcher::new(FakeHttp::default(), "abc123", &dir).interval(Duration::from_millis(500));
+        let now = Instant::now();
+        assert!(fetcher.fetch(2021, 4).is_err());
+        assert!(now.elapsed() >= Duration::from_millis(400));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rate_limit_stamp() {
+        let dir = temp_dir("stamp");
+        let stamp = dir.join(fetch::STAMP_FILE);
+        let mut first = RateLimiter::new(Duration::from_millis(50)).stamp(&stamp);
+        assert_eq!(first.wait(), Duration::ZERO);
+        assert!(fs::read_to_string(&stamp).unwrap().parse::<u128>().is_ok());
+        let mut second = RateLimiter::new(Duration::from_millis(50)).stamp(&stamp);
+        assert!(second.wait() > Duration::ZERO);
+
+        fs::write(&stamp, "not a time").unwrap();
+        let mut third = RateLimiter::new(Duration::from_secs(60)).stamp(&stamp);
+        assert_eq!(third.wait(), Duration::ZERO);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_session() {
+        let path = temp_dir("session");
+        assert_eq!(fetch::session(Some("53a1".into()), &path).unwrap(), "53a1");
+        let err = fetch::session(None, &path).unwrap_err();
+        assert!(err.to_string().starts_with("no session cookie"));
+
+        fs::write(&path, "session=53a1\n").unwrap();
+        assert_eq!(fetch::session(Some("".into()), &path).unwrap(), "53a1");
+        fs::write(&path, "53a1\r\nCookie: x").unwrap();
+        assert!(fetch::session(None, &path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    // Answers a single request with `body`, and hands back the request's
+    // head lines and body.
+    fn serve_once(path: &str, body: &'static str) -> (String, JoinHandle<(Vec<String>, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}{}", listener.local_addr().unwrap(), path);
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = vec![];
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                head.push(line.trim_end().to_string());
+                line.clear();
+            }
+            let length = head
+                .iter()
+                .find_map(|h| h.strip_prefix("Content-Length: "))
+                .map_or(0, |n| n.parse().unwrap());
+            let mut form = vec![0; length];
+            reader.read_exact(&mut form).unwrap();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            (head, String::from_utf8(form).unwrap())
+        });
+        (url, server)
+    }
+
+    fn has_curl() -> bool {
+        Command::new("curl").arg("--version").output().is_ok()
+    }
+
+    // The real backend against a one-shot local server, so no network is
+    // needed; skipped where curl is not installed.
+    #[test]
+    fn test_curl_get() {
+        if !has_curl() {
+            return;
+        }
+        let (url, server) = serve_once("/2021/day/1/input", "199\n200\n");
+        let response = Curl.get(&url, &[("Cookie", "session=abc123")]).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "199\n200\n");
+        let (head, _) = server.join().unwrap();
+        assert_eq!(head[0], "GET /2021/day/1/input HTTP/1.1");
+        assert!(head.contains(&"Cookie: session=abc123".to_string()));
+    }
+
+    #[test]
+    fn test_curl_post() {
+        if !has_curl() {
+            return;
+        }
+        let (url, server) = serve_once("/2021/day/1/answer", "<p>That's the right answer!</p>");
+        let response = Curl
+            .post(&url, &[("Cookie", "session=abc123")], "level=1&answer=7")
+            .unwrap();
+        assert_eq!(response.body, "<p>That's the right answer!</p>");
+        let (head, form) = server.join().unwrap();
+        assert_eq!(head[0], "POST /2021/day/1/answer HTTP/1.1");
+        assert_eq!(form, "level=1&answer=7");
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::HashSet;
+
+// SplitMix64: tiny, fast and good enough to shuffle puzzle inputs. The same
+// seed always produces the same input.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in `lo..=hi`; the modulo bias is irrelevant at these ranges.
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        lo + (self.next_u64() % (hi - lo + 1) as u64) as i64
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range(0, i as i64) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+pub type Generate = fn(&mut Rng, usize) -> String;
+
+#[derive(Clone, Copy)]
+pub struct Generator {
+    pub year: u16,
+    pub day: u8,
+    // Close to the size of the real input, in the unit `generate` counts.
+    pub default_size: usize,
+    pub generate: Generate,
+}
+
+pub fn generators() -> Vec<Generator> {
+    vec![
+        Generator {
+            year: 2021,
+            day: 1,
+            default_size: 2000,
+            generate: sonar_deep,
+        },
+        Generator {
+            year: 2021,
+            day: 2,
+            default_size: 1000,
+            generate: dive,
+        },
+        Generator {
+            year: 2021,
+            day: 3,
+            default_size: 1000,
+            generate: binary_diagnostic,
+        },
+        Generator {
+            year: 2021,
+            day: 4,
+            default_size: 100,
+            generate: giant_squid,
+        },
+        Generator {
+            year: 2021,
+            day: 5,
+            default_size: 500,
+            generate: hydrothermal_venture,
+        },
+    ]
+}
+
+pub fn find(year: u16, day: u8) -> Option<Generator> {
+    generators()
+        .into_iter()
+        .find(|g| g.year == year && g.day == day)
+}
+
+// `size` depths of a random walk that trends downwards, like the sea floor.
+pub fn sonar_deep(rng: &mut Rng, size: usize) -> String {
+    let mut depth = rng.range(100, 200);
+    let mut out = String::new();
+    for _ in 0..size {
+        out += &format!("{}\n", depth);
+        depth = (depth + rng.range(-10, 20)).max(0);
+    }
+    out
+}
+
+// `size` commands; the submarine never rises above the surface.
+pub fn dive(rng: &mut Rng, size: usize) -> String {
+    let mut aim = 0;
+    let mut out = String::new();
+    for _ in 0..size {
+        let value = rng.range(1, 9);
+        let command = match rng.range(0, 2) {
+            0 => "forward",
+            1 if aim >= value => {
+                aim -= value;
+                "up"
+            }
+            _ => {
+                aim += value;
+                "down"
+            }
+        };
+        out += &format!("{} {}\n", command, value);
+    }
+    out
+}
+
+// `size` distinct numbers, wide enough that at most a quarter of the values
+// are taken.
+pub fn binary_diagnostic(rng: &mut Rng, size: usize) -> String {
+    let mut width = 5;
+    while width < 31 && (1_usize << width) < size * 4 {
+        width += 1;
+    }
+    let mut seen = HashSet::new();
+    let mut out = String::new();
+    while seen.len() < size.min(1 << width) {
+        let n = rng.range(0, (1 << width) - 1);
+        if seen.insert(n) {
+            out += &format!("{:0width$b}\n", n, width = width);
+        }
+    }
+    out
+}
+
+// Draws every number from 0 to 99, followed by `size` boards of 25 distinct
+// numbers, so every board wins eventually.
+pub fn giant_squid(rng: &mut Rng, size: usize) -> String {
+    let mut draws: Vec<i64> = (0..100).collect();
+    rng.shuffle(&mut draws);
+    let draws: Vec<String> = draws.iter().map(|n| n.to_string()).collect();
+    let mut out = draws.join(",") + "\n";
+    for _ in 0..size {
+        let mut numbers: Vec<i64> = (0..100).collect();
+        rng.shuffle(&mut numbers);
+        out += "\n";
+        for row in numbers[..25].chunks(5) {
+            let row: Vec<String> = row.iter().map(|n| format!("{:>2}", n)).collect();
+            out += &format!("{}\n", row.join(" "));
+        }
+    }
+    out
+}
+
+// `size` horizontal, vertical and 45 degree diagonal segments on a 1000 by
+// 1000 floor.
+pub fn hydrothermal_venture(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let (x1, y1) = (rng.range(0, 999), rng.range(0, 999));
+        let length = rng.range(1, 300);
+        let (dx, dy) = match rng.range(0, 3) {
+            0 => (1, 0),
+            1 => (0, 1),
+            2 => (1, 1),
+            _ => (1, -1),
+        };
+        let (dx, dy) = if rng.range(0, 1) == 0 {
+            (dx, dy)
+        } else {
+            (-dx, -dy)
+        };
+        // Shorten the segment until it stays on the floor.
+        let fits =
+            |l: i64| (0..1000).contains(&(x1 + dx * l)) && (0..1000).contains(&(y1 + dy * l));
+        let length = (1..=length).rev().find(|&l| fits(l)).unwrap_or(0);
+        out += &format!(
+            "{},{} -> {},{}\n",
+            x1,
+            y1,
+            x1 + dx * length,
+            y1 + dy * length
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        generate::{find, generators, Rng},
+        registry,
+    };
+
+    #[test]
+    fn test_rng() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..100 {
+            let n = a.range(-3, 3);
+            assert_eq!(n, b.range(-3, 3));
+            assert!((-3..=3).contains(&n));
+        }
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+
+        let mut items: Vec<u32> = (0..50).collect();
+        a.shuffle(&mut items);
+        items.sort_unstable();
+        assert_eq!(items, (0..50).collect::<Vec<u32>>());
+    }
+
+    // Whatever the seed or size, a generated input must parse.
+    #[test]
+    fn test_generators() {
+        for generator in generators() {
+            let day = registry::find(generator.year, generator.day).unwrap();
+            for (seed, size) in [(1, 1), (2, 7), (3, generator.default_size)] {
+                let input = (generator.generate)(&mut Rng::new(seed), size);
+                assert_eq!(input, (generator.generate)(&mut Rng::new(seed), size));
+                let solution = (day.parse)(&input).unwrap_or_else(|err| {
+                    panic!("day {} seed {} size {}: {}", day.day, seed, size, err)
+                });
+                solution.part1();
+            }
+        }
+        assert!(find(2021, 4).is_some());
+        assert!(find(2021, 25).is_none());
+    }
+
+    // Far past the real inputs, where an overflow or a blowup would show.
+    #[test]
+    fn test_large_inputs() {
+        for generator in generators() {
+            let day = registry::find(generator.year, generator.day).unwrap();
+            let size = generator.default_size * 50;
+            let input = (generator.generate)(&mut Rng::new(4), size);
+            let solution = (day.parse)(&input)
+                .unwrap_or_else(|err| panic!("day {} size {}: {}", day.day, size, err));
+            solution.part1();
+            solution.part2();
+        }
+    }
+
+    #[test]
+    fn test_sizes() {
+        let mut rng = Rng::new(7);
+        let input = (find(2021, 1).unwrap().generate)(&mut rng, 20_000);
+        assert_eq!(input.lines().count(), 20_000);
+        let input = (find(2021, 3).unwrap().generate)(&mut rng, 5000);
+        assert_eq!(input.lines().count(), 5000);
+        assert_eq!(input.lines().next().unwrap().len(), 15);
+        let input = (find(2021, 4).unwrap().generate)(&mut rng, 3);
+        assert_eq!(input.lines().count(), 1 + 3 * 6);
+    }
+}
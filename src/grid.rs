@@ -0,0 +1,397 @@
+use std::{
+    collections::HashMap,
+    fmt, iter,
+    ops::{Add, Index, IndexMut},
+};
+
+// x grows to the right and y downwards, the way puzzle text lays out a grid.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+const NEIGHBOURS4: [Point; 4] = [
+    Point { x: 0, y: -1 },
+    Point { x: -1, y: 0 },
+    Point { x: 1, y: 0 },
+    Point { x: 0, y: 1 },
+];
+
+const NEIGHBOURS8: [Point; 8] = [
+    Point { x: -1, y: -1 },
+    Point { x: 0, y: -1 },
+    Point { x: 1, y: -1 },
+    Point { x: -1, y: 0 },
+    Point { x: 1, y: 0 },
+    Point { x: -1, y: 1 },
+    Point { x: 0, y: 1 },
+    Point { x: 1, y: 1 },
+];
+
+impl Point {
+    pub fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    // Every point from `self` to `to`, both included, if they share a row, a
+    // column or a 45 degree diagonal.
+    pub fn segment(self, to: Point) -> Option<impl Iterator<Item = Point>> {
+        let (dx, dy) = (to.x - self.x, to.y - self.y);
+        if dx != 0 && dy != 0 && dx.abs() != dy.abs() {
+            return None;
+        }
+        let step = Point::new(dx.signum(), dy.signum());
+        let length = dx.abs().max(dy.abs());
+        Some((0..=length).map(move |i| Point::new(self.x + step.x * i, self.y + step.y * i)))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Cells<T> {
+    Dense(Vec<T>),
+    Sparse(HashMap<Point, T>),
+}
+
+// A dense grid covers `0..width` by `0..height` and stores every cell. A
+// sparse one is unbounded, stores only the cells written to, and reads
+// `T::default()` everywhere else.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    min: Point,
+    max: Point,
+    cells: Cells<T>,
+    default: T,
+}
+
+impl<T: Clone + Default> Grid<T> {
+    pub fn dense(width: usize, height: usize) -> Grid<T> {
+        Grid {
+            min: Point::new(0, 0),
+            max: Point::new(width as i64 - 1, height as i64 - 1),
+            cells: Cells::Dense(vec![T::default(); width * height]),
+            default: T::default(),
+        }
+    }
+
+    // Panics on rows of different lengths.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let width = rows.first().map_or(0, |r| r.len());
+        assert!(
+            rows.iter().all(|r| r.len() == width),
+            "grid rows must all have the same length"
+        );
+        let mut grid = Grid::dense(width, rows.len());
+        grid.cells = Cells::Dense(rows.into_iter().flatten().collect());
+        grid
+    }
+
+    pub fn sparse() -> Grid<T> {
+        Grid {
+            min: Point::new(0, 0),
+            max: Point::new(-1, -1),
+            cells: Cells::Sparse(HashMap::new()),
+            default: T::default(),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1).max(0) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1).max(0) as usize
+    }
+
+    // The corners of the smallest rectangle holding every stored cell.
+    pub fn bounds(&self) -> (Point, Point) {
+        (self.min, self.max)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        match self.cells {
+            Cells::Dense(_) => {
+                (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+            }
+            Cells::Sparse(_) => true,
+        }
+    }
+
+    fn offset(&self, p: Point) -> usize {
+        (p.y - self.min.y) as usize * self.width() + (p.x - self.min.x) as usize
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        match &self.cells {
+            Cells::Dense(cells) if self.contains(p) => Some(&cells[self.offset(p)]),
+            Cells::Dense(_) => None,
+            Cells::Sparse(cells) => Some(cells.get(&p).unwrap_or(&self.default)),
+        }
+    }
+
+    // On a sparse grid this stores the cell, so it counts towards the bounds.
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T>
+    where
+        T: Clone,
+    {
+        if let Cells::Sparse(cells) = &self.cells {
+            if cells.is_empty() {
+                self.min = p;
+                self.max = p;
+            } else {
+                self.min = Point::new(self.min.x.min(p.x), self.min.y.min(p.y));
+                self.max = Point::new(self.max.x.max(p.x), self.max.y.max(p.y));
+            }
+        }
+        let offset = if self.contains(p) {
+            Some(self.offset(p))
+        } else {
+            None
+        };
+        let default = &self.default;
+        match (&mut self.cells, offset) {
+            (Cells::Dense(cells), Some(offset)) => Some(&mut cells[offset]),
+            (Cells::Dense(_), None) => None,
+            (Cells::Sparse(cells), _) => Some(cells.entry(p).or_insert_with(|| default.clone())),
+        }
+    }
+
+    // Stored cells; in reading order on a dense grid, in no particular order
+    // on a sparse one.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let (dense, sparse) = match &self.cells {
+            Cells::Dense(cells) => (Some(cells), None),
+            Cells::Sparse(cells) => (None, Some(cells)),
+        };
+        let (min, width) = (self.min, self.width().max(1));
+        let dense = dense.into_iter().flat_map(move |cells| {
+            cells.iter().enumerate().map(move |(i, v)| {
+                let p = Point::new((i % width) as i64, (i / width) as i64);
+                (p + min, v)
+            })
+        });
+        let sparse = sparse
+            .into_iter()
+            .flat_map(|cells| cells.iter().map(|(&p, v)| (p, v)));
+        dense.chain(sparse)
+    }
+
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        NEIGHBOURS4
+            .iter()
+            .map(move |&d| p + d)
+            .filter(move |&n| self.contains(n))
+    }
+
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        NEIGHBOURS8
+            .iter()
+            .map(move |&d| p + d)
+            .filter(move |&n| self.contains(n))
+    }
+
+    // Walks from `start` by `step` for as long as it stays within the bounds.
+    pub fn line(&self, start: Point, step: Point) -> impl Iterator<Item = (Point, &T)> {
+        let (min, max) = (self.min, self.max);
+        let inside =
+            move |p: &Point| (min.x..=max.x).contains(&p.x) && (min.y..=max.y).contains(&p.y);
+        // A step of zero stays on `start`, so it is visited once.
+        let length = if step.x == 0 && step.y == 0 {
+            1
+        } else {
+            usize::MAX
+        };
+        iter::successors(Some(start), move |&p| Some(p + step))
+            .take(length)
+            .take_while(inside)
+            .filter_map(move |p| self.get(p).map(|v| (p, v)))
+    }
+
+    pub fn row(&self, y: i64) -> impl Iterator<Item = (Point, &T)> {
+        self.line(Point::new(self.min.x, y), Point::new(1, 0))
+    }
+
+    pub fn column(&self, x: i64) -> impl Iterator<Item = (Point, &T)> {
+        self.line(Point::new(x, self.min.y), Point::new(0, 1))
+    }
+
+    // The diagonal through the top left corner, and the one through the top
+    // right corner.
+    pub fn diagonal(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.line(self.min, Point::new(1, 1))
+    }
+
+    pub fn anti_diagonal(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.line(Point::new(self.max.x, self.min.y), Point::new(-1, 1))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p)
+            .unwrap_or_else(|| panic!("point {} is outside the grid", p))
+    }
+}
+
+impl<T: Clone> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("point {} is outside the grid", p))
+    }
+}
+
+// One line per row, cells padded to the widest one and separated by a space
+// once they take more than one character. Cells a sparse grid never stored
+// show as '.'.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cell = |p: Point| match &self.cells {
+            Cells::Sparse(cells) if !cells.contains_key(&p) => ".".to_string(),
+            _ => self.get(p).map_or(String::new(), |v| v.to_string()),
+        };
+        let mut rows = vec![];
+        for y in self.min.y..=self.max.y {
+            rows.push(
+                (self.min.x..=self.max.x)
+                    .map(|x| cell(Point::new(x, y)))
+                    .collect::<Vec<String>>(),
+            );
+        }
+        let width = rows.iter().flatten().map(|c| c.len()).max().unwrap_or(0);
+        let separator = if width > 1 { " " } else { "" };
+        for row in rows {
+            let row: Vec<String> = row.iter().map(|c| format!("{:>1$}", c, width)).collect();
+            writeln!(f, "{}", row.join(separator))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        grid::{Grid, Point},
+        memory,
+    };
+
+    fn board() -> Grid<i32> {
+        Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]])
+    }
+
+    #[test]
+    fn test_dense() {
+        let mut grid = board();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(2, 1)], 6);
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        grid[Point::new(0, 1)] = 40;
+        assert_eq!(grid.to_string(), " 1  2  3\n40  5  6\n");
+
+        let points: Vec<Point> = grid.iter().map(|(p, _)| p).collect();
+        assert_eq!(points[4], Point::new(1, 1));
+        let empty: Grid<u8> = Grid::dense(0, 0);
+        assert_eq!(empty.iter().count(), 0);
+        assert_eq!(empty.to_string(), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the grid")]
+    fn test_dense_outside() {
+        board()[Point::new(-1, 0)] += 1;
+    }
+
+    #[test]
+    fn test_sparse() {
+        let mut grid: Grid<i32> = Grid::sparse();
+        assert_eq!(grid.width(), 0);
+        assert_eq!(grid[Point::new(-5, 7)], 0);
+        grid[Point::new(2, 1)] += 1;
+        grid[Point::new(-1, 0)] += 2;
+        grid[Point::new(2, 1)] += 1;
+        assert_eq!(grid.bounds(), (Point::new(-1, 0), Point::new(2, 1)));
+        assert_eq!(grid.to_string(), "2...\n...2\n");
+        assert_eq!(grid.iter().count(), 2);
+        assert!(grid.contains(Point::new(100, 100)));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = board();
+        let corner: Vec<Point> = grid.neighbours4(Point::new(0, 0)).collect();
+        assert_eq!(corner, vec![Point::new(1, 0), Point::new(0, 1)]);
+        assert_eq!(grid.neighbours8(Point::new(1, 0)).count(), 5);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 5);
+        let sparse: Grid<i32> = Grid::sparse();
+        assert_eq!(sparse.neighbours8(Point::new(0, 0)).count(), 8);
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = board();
+        let values =
+            |line: Vec<(Point, &i32)>| line.into_iter().map(|(_, &v)| v).collect::<Vec<i32>>();
+        assert_eq!(values(grid.row(1).collect()), vec![4, 5, 6]);
+        assert_eq!(values(grid.column(2).collect()), vec![3, 6]);
+        assert_eq!(values(grid.diagonal().collect()), vec![1, 5]);
+        assert_eq!(values(grid.anti_diagonal().collect()), vec![3, 5]);
+        assert_eq!(grid.row(2).count(), 0);
+        assert_eq!(grid.line(Point::new(1, 1), Point::new(0, 0)).count(), 1);
+        assert_eq!(grid.line(Point::new(3, 0), Point::new(0, 0)).count(), 0);
+
+        let mut sparse: Grid<i32> = Grid::sparse();
+        sparse[Point::new(-1, 0)] = 7;
+        sparse[Point::new(1, 2)] = 9;
+        assert_eq!(values(sparse.row(0).collect()), vec![7, 0, 0]);
+        assert_eq!(values(sparse.anti_diagonal().collect()), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_iterators_do_not_allocate() {
+        let grid = board();
+        let (sum, stats) = memory::measure(|| {
+            grid.iter().map(|(_, v)| v).sum::<i32>()
+                + grid.row(1).map(|(_, v)| v).sum::<i32>()
+                + grid.column(0).map(|(_, v)| v).sum::<i32>()
+                + grid.diagonal().count() as i32
+        });
+        assert_eq!(sum, 21 + 15 + 5 + 2);
+        assert_eq!(stats.unwrap().count, 0);
+    }
+
+    #[test]
+    fn test_segment() {
+        let points: Vec<Point> = Point::new(8, 0)
+            .segment(Point::new(6, 2))
+            .unwrap()
+            .collect();
+        assert_eq!(
+            points,
+            vec![Point::new(8, 0), Point::new(7, 1), Point::new(6, 2)]
+        );
+        assert_eq!(
+            Point::new(2, 2).segment(Point::new(2, 2)).unwrap().count(),
+            1
+        );
+        assert!(Point::new(0, 0).segment(Point::new(1, 2)).is_none());
+        assert_eq!(Point::new(3, -4).to_string(), "3,-4");
+    }
+}
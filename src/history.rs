@@ -0,0 +1,264 @@
+use std::{
+    error::Error,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+    time::Duration,
+};
+
+use crate::{
+    records,
+    report::{Outcome, RunRecord},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub run: u64,
+    pub year: u16,
+    pub day: u8,
+    pub name: String,
+    pub elapsed_time: Duration,
+}
+
+impl Entry {
+    // Only solved days are worth keeping; a failure's timing says nothing
+    // about the solution's speed.
+    pub fn from_record(run: u64, record: &RunRecord) -> Option<Entry> {
+        match record.outcome {
+            Outcome::Solved(_) => Some(Entry {
+                run,
+                year: record.year,
+                day: record.day,
+                name: record.name.clone(),
+                elapsed_time: record.elapsed_time,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct History {
+    entries: Vec<Entry>,
+}
+
+impl History {
+    pub fn parse(input_str: &str) -> Result<History, Box<dyn Error>> {
+        let entries = records::parse(input_str, "history", |fields| {
+            let entry = match fields {
+                [run, year, day, name, elapsed_us] => {
+                    match (run.parse(), year.parse(), day.parse(), elapsed_us.parse()) {
+                        (Ok(run), Ok(year), Ok(day), Ok(elapsed_us)) => Some(Entry {
+                            run,
+                            year,
+                            day,
+                            name: name.to_string(),
+                            elapsed_time: Duration::from_micros(elapsed_us),
+                        }),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            entry.ok_or_else(|| "expected 'run year day name elapsed_us'".to_string())
+        })?;
+        Ok(History { entries })
+    }
+
+    pub fn from_file_or_empty(path: &Path) -> Result<History, Box<dyn Error>> {
+        Self::parse(&records::read_or_empty(path)?)
+    }
+
+    pub fn append(path: &Path, entries: &[Entry]) -> io::Result<()> {
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if is_new {
+            writeln!(file, "# run year day name elapsed_us")?;
+        }
+        for e in entries {
+            writeln!(
+                file,
+                "{} {} {:02} {} {}",
+                e.run,
+                e.year,
+                e.day,
+                e.name,
+                e.elapsed_time.as_micros()
+            )?;
+        }
+        Ok(())
+    }
+
+    // The latest recorded timing of a day, whichever run it came from, so a
+    // run of a single day still has a baseline for it.
+    pub fn baseline(&self, year: u16, day: u8) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.year == year && e.day == day)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delta {
+    pub name: String,
+    pub baseline: Option<Duration>,
+    pub current: Duration,
+}
+
+impl Delta {
+    // Relative change in percent; positive is slower.
+    pub fn change(&self) -> Option<f64> {
+        let baseline = self.baseline?.as_secs_f64();
+        if baseline == 0.0 {
+            return None;
+        }
+        Some((self.current.as_secs_f64() - baseline) / baseline * 100.0)
+    }
+
+    pub fn regressed(&self, threshold: u32) -> bool {
+        self.change().is_some_and(|c| c > threshold as f64)
+    }
+}
+
+pub fn compare(history: &History, records: &[RunRecord]) -> Vec<Delta> {
+    records
+        .iter()
+        .filter(|r| matches!(r.outcome, Outcome::Solved(_)))
+        .map(|r| Delta {
+            name: r.name.clone(),
+            baseline: history.baseline(r.year, r.day).map(|e| e.elapsed_time),
+            current: r.elapsed_time,
+        })
+        .collect()
+}
+
+pub fn write_deltas(w: &mut dyn Write, deltas: &[Delta], threshold: u32) -> io::Result<()> {
+    writeln!(w)?;
+    for (i, delta) in deltas.iter().enumerate() {
+        let baseline = match delta.baseline {
+            Some(b) => format!("{:>7} us", b.as_micros()),
+            None => format!("{:>10}", "-"),
+        };
+        let change = match delta.change() {
+            Some(c) => format!("{:>+7.1}%", c),
+            None => format!("{:>8}", "new"),
+        };
+        writeln!(
+            w,
+            "Puzzle {0} [{1:^30}] {2} -> {3:>7} us {4}{5}",
+            i,
+            delta.name,
+            baseline,
+            delta.current.as_micros(),
+            change,
+            if delta.regressed(threshold) {
+                " SLOWER"
+            } else {
+                ""
+            }
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process, slice, time::Duration};
+
+    use crate::{
+        history::{compare, write_deltas, Delta, Entry, History},
+        journey::DayReport,
+        report::{Outcome, RunRecord},
+    };
+
+    fn record(day: u8, name: &str, elapsed_ms: u64, outcome: Outcome) -> RunRecord {
+        RunRecord {
+            year: 2021,
+            day,
+            name: name.to_string(),
+            title: name.to_string(),
+            elapsed_time: Duration::from_millis(elapsed_ms),
+            alloc_stats: None,
+            outcome,
+        }
+    }
+
+    fn solved() -> Outcome {
+        Outcome::Solved(DayReport {
+            parse_time: Duration::ZERO,
+            parts: vec![],
+        })
+    }
+
+    #[test]
+    fn test_parse_and_baseline() {
+        let history = History::parse(
+            "# run year day name elapsed_us\n1 2021 04 giant_squid 24000\n2 2021 01 sonar_deep 900\n3 2021 04 giant_squid 25000\n",
+        )
+        .unwrap();
+        assert_eq!(
+            history.baseline(2021, 4).unwrap().elapsed_time,
+            Duration::from_millis(25)
+        );
+        assert_eq!(history.baseline(2021, 1).unwrap().run, 2);
+        assert!(history.baseline(2021, 2).is_none());
+        assert!(History::parse("1 2021 04 giant_squid").is_err());
+        assert!(History::parse("1 2021 x giant_squid 5").is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let history =
+            History::parse("1 2021 01 sonar_deep 1000\n1 2021 04 giant_squid 24000").unwrap();
+        let records = vec![
+            record(1, "sonar_deep", 1, solved()),
+            record(2, "dive", 3, solved()),
+            record(3, "binary_diagnostic", 3, Outcome::Error("x".to_string())),
+            record(4, "giant_squid", 40, solved()),
+        ];
+        let deltas = compare(&history, &records);
+        assert_eq!(deltas.len(), 3);
+        assert_eq!(deltas[0].change(), Some(0.0));
+        assert_eq!(deltas[1].change(), None);
+        assert!(!deltas[1].regressed(20));
+        assert!(deltas[2].regressed(20));
+        assert!(!deltas[2].regressed(80));
+
+        let mut out = vec![];
+        write_deltas(&mut out, &deltas, 20).unwrap();
+        let table = String::from_utf8(out).unwrap();
+        assert!(table.contains(
+            "Puzzle 2 [         giant_squid          ]   24000 us ->   40000 us   +66.7% SLOWER"
+        ));
+        assert!(table.contains(
+            "Puzzle 1 [             dive             ]          - ->    3000 us      new\n"
+        ));
+    }
+
+    #[test]
+    fn test_append() {
+        let path = env::temp_dir().join(format!("aoc-history-{}", process::id()));
+        let entry = Entry::from_record(7, &record(4, "giant_squid", 24, solved())).unwrap();
+        History::append(&path, slice::from_ref(&entry)).unwrap();
+        History::append(&path, &[entry]).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "# run year day name elapsed_us\n7 2021 04 giant_squid 24000\n7 2021 04 giant_squid 24000\n"
+        );
+        let history = History::from_file_or_empty(&path).unwrap();
+        assert_eq!(history.baseline(2021, 4).unwrap().run, 7);
+        fs::remove_file(&path).unwrap();
+
+        assert!(
+            Entry::from_record(7, &record(1, "x", 1, Outcome::Panicked("p".to_string()))).is_none()
+        );
+        let delta = Delta {
+            name: "x".to_string(),
+            baseline: Some(Duration::ZERO),
+            current: Duration::from_millis(1),
+        };
+        assert_eq!(delta.change(), None);
+    }
+}
@@ -1,79 +1,196 @@
-use std::path::Path;
+use std::{
+    error::Error,
+    fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 use crate::{
-    d00_aoc::InputReader, d01_sonar_deep::SonarDeep, d02_dive::Dive,
-    d03_binary_diagnostic::BinaryDiagnostic, d04_giant_squid::GiantSquid,
-    d05_hydrothermal_veture::HydroThermalVenture,
+    answers::{Answers, Verdict},
+    d00_aoc::Answer,
+    registry::Day,
+    utils::{bench, BenchResult},
 };
 
-pub fn run_sonar_deep() {
-    println!("Day 1 of Advent :: Sonar Deep");
-    let input_path = Path::new("./inputs/d01_input.txt");
-    let a = SonarDeep::from_file(input_path).unwrap();
-    println!("  Sonar Deep increases = {}", a.measurements());
-    println!(
-        "  Sonar Deep increases on 3 window = {}",
-        a.measurements_window_sum(3)
-    );
+#[derive(Debug)]
+pub struct InputError {
+    pub path: PathBuf,
+    pub source: io::Error,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path == Path::new("-") {
+            write!(f, "cannot read input from stdin: {}", self.source)
+        } else {
+            write!(
+                f,
+                "cannot read input {}: {}",
+                self.path.display(),
+                self.source
+            )
+        }
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+// `-` reads the whole of stdin.
+pub fn load_input(input_path: &Path) -> Result<String, InputError> {
+    let result = if input_path == Path::new("-") {
+        let mut input_str = String::new();
+        io::stdin()
+            .read_to_string(&mut input_str)
+            .map(|_| input_str)
+    } else {
+        fs::read_to_string(input_path)
+    };
+    result.map_err(|source| InputError {
+        path: input_path.to_path_buf(),
+        source,
+    })
 }
 
-pub fn run_dive() {
-    println!("Day 2 of Advent :: Dive");
-    let input_path = Path::new("./inputs/d02_input.txt");
-    let a = Dive::from_file(input_path).unwrap();
-    let dive_forward = a.forward();
-    let dive_depth = a.wrong_depth();
-    println!(
-        "  Part 1 :: forward = {}, depth = {}, total = {}",
-        dive_forward,
-        dive_depth,
-        dive_forward * dive_depth
-    );
-    let dive_depth = a.depth();
-    println!(
-        "  Part 2 :: forward = {}, depth = {}, total = {}",
-        dive_forward,
-        dive_depth,
-        dive_forward * dive_depth
-    );
+#[derive(Debug, Clone)]
+pub struct PartReport {
+    pub part: u8,
+    pub answer: Answer,
+    pub elapsed_time: Duration,
+    pub verdict: Verdict,
 }
 
-pub fn run_binary_diagnostic() {
-    println!("Day 3 of Advent :: Binary Diagnostic");
-    let input_path = Path::new("./inputs/d03_input.txt");
-    let a = BinaryDiagnostic::from_file(input_path).unwrap();
-    println!(
-        "  Binary Diagnostic Part 1 :: gama = {}, epsilon = {}, diagnostic = {}",
-        a.gamma(),
-        a.epsilon(),
-        a.gamma() * a.epsilon()
-    );
-    println!(
-        "  Binary Diagnostic Part 2 :: oxygen = {}, co2 = {}, diagnostic = {}",
-        a.oxygen(),
-        a.co2(),
-        a.oxygen() * a.co2()
-    );
+#[derive(Debug, Clone)]
+pub struct DayReport {
+    pub parse_time: Duration,
+    pub parts: Vec<PartReport>,
 }
 
-pub fn run_giant_squid() {
-    println!("Day 4 of Advent :: Giant Squid");
-    let input_path = Path::new("./inputs/d04_input.txt");
-    let mut a = GiantSquid::from_file(input_path).unwrap();
-    println!(
-        "  Bingo Part 1 :: winner code {}",
-        a.find_first_winner_code()
-    );
-    println!(
-        "  Bingo Part 2 :: winner code {}",
-        a.find_last_winner_code()
-    );
+pub fn run_day(
+    day: &Day,
+    part: Option<u8>,
+    input_path: &Path,
+    answers: &Answers,
+) -> Result<DayReport, Box<dyn Error>> {
+    let input_str = load_input(input_path)?;
+    let now = Instant::now();
+    let a = (day.parse)(&input_str)?;
+    let parse_time = now.elapsed();
+    let mut parts = vec![];
+    for n in [1, 2] {
+        if part.is_some() && part != Some(n) {
+            continue;
+        }
+        let now = Instant::now();
+        let answer = if n == 1 { a.part1() } else { a.part2() };
+        let elapsed_time = now.elapsed();
+        let verdict = answers.check(day.day, n, &answer);
+        parts.push(PartReport {
+            part: n,
+            answer,
+            elapsed_time,
+            verdict,
+        });
+    }
+    Ok(DayReport { parse_time, parts })
 }
 
-pub fn run_hydrothermal_venture() {
-    println!("Day 5 of Advent :: Hydrothermal Venture");
-    let input_path = Path::new("./inputs/d05_input.txt");
-    let a = HydroThermalVenture::from_file(input_path).unwrap();
-    println!("  Vents Part 1 :: overlaps {}", a.overlaps());
-    println!("  Vents Part 2 :: overlaps diagonal {}", a.overlaps_diag());
+pub fn bench_day(
+    day: &Day,
+    input_path: &Path,
+    warmup: usize,
+    iterations: usize,
+) -> Result<BenchResult, Box<dyn Error>> {
+    let input_str = load_input(input_path)?;
+    bench(day.parse, &input_str, warmup, iterations)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io, path::Path};
+
+    use crate::{
+        answers::{Answers, Verdict},
+        journey::{load_input, run_day},
+        registry,
+        utils::{Puzzle, Status},
+    };
+
+    #[test]
+    fn test_load_input_missing_file() {
+        let err = load_input(Path::new("inputs/d99_input.txt")).unwrap_err();
+        assert_eq!(err.source.kind(), io::ErrorKind::NotFound);
+        assert!(err
+            .to_string()
+            .starts_with("cannot read input inputs/d99_input.txt: "));
+    }
+
+    #[test]
+    fn test_run_day() {
+        let day = registry::find(2021, 4).unwrap();
+        let answers = Answers::parse("04 1 4512\n04 2 1").unwrap();
+        let report = run_day(
+            &day,
+            None,
+            Path::new("src/test_inputs/2021/d04_test_input.txt"),
+            &answers,
+        )
+        .unwrap();
+        assert_eq!(report.parts.len(), 2);
+        assert_eq!(report.parts[0].verdict, Verdict::Pass);
+        assert_eq!(report.parts[1].verdict, Verdict::Fail("1".to_string()));
+
+        let report = run_day(
+            &day,
+            Some(2),
+            Path::new("src/test_inputs/2021/d04_test_input.txt"),
+            &answers,
+        )
+        .unwrap();
+        assert_eq!(report.parts.len(), 1);
+        assert_eq!(report.parts[0].part, 2);
+    }
+
+    // Runs every registered day against its example in src/test_inputs, so a
+    // new example only needs the input file and its lines in answers.txt.
+    #[test]
+    fn test_examples() {
+        let mut failures = vec![];
+        for day in registry::days() {
+            let path = day.example_path();
+            if !fs::metadata(&path).is_ok_and(|m| m.len() > 0) {
+                continue;
+            }
+            let answers_path = path.with_file_name("answers.txt");
+            let answers = Answers::from_file_or_empty(&answers_path).unwrap();
+            let puzzle = Puzzle::run(
+                day.name,
+                Box::new(|| run_day(&day, None, &path, &answers).map_err(|e| e.to_string())),
+            );
+            match puzzle.status() {
+                Status::Done(Ok(report)) => {
+                    for p in &report.parts {
+                        if p.verdict != Verdict::Pass {
+                            failures.push(format!(
+                                "{} day {} part {}: {} [{}]",
+                                day.year, day.day, p.part, p.answer, p.verdict
+                            ));
+                        }
+                    }
+                }
+                Status::Done(Err(err)) => {
+                    failures.push(format!("{} day {}: {}", day.year, day.day, err))
+                }
+                Status::Panicked(msg) => {
+                    failures.push(format!("{} day {}: panicked: {}", day.year, day.day, msg))
+                }
+                Status::TimedOut(_) => unreachable!(),
+            }
+        }
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
 }
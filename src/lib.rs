@@ -1,8 +1,21 @@
+pub mod answers;
+pub mod cli;
 pub mod d00_aoc;
-pub mod d01_sonar_deep;
-pub mod d02_dive;
-pub mod d03_binary_diagnostic;
-pub mod d04_giant_squid;
-pub mod d05_hydrothermal_veture;
+pub mod fetch;
+pub mod generate;
+pub mod grid;
+pub mod history;
 pub mod journey;
+pub mod memory;
+pub mod parse;
+pub mod records;
+pub mod reference;
+pub mod registry;
+pub mod repl;
+pub mod report;
+pub mod scaffold;
+pub mod submit;
+pub mod trace;
 pub mod utils;
+pub mod watch;
+pub mod y2021;
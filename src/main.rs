@@ -1,27 +1,437 @@
+use std::{
+    env,
+    error::Error,
+    io::{self, Write},
+    path::Path,
+    process,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
 use advent_of_code_rust::{
-    journey::{
-        run_binary_diagnostic, run_dive, run_giant_squid, run_hydrothermal_venture, run_sonar_deep,
+    answers::Answers,
+    cli::{
+        FetchInputs, GenerateInput, NewDay, Options, SubmitAnswer, FETCH_USAGE, GENERATE_USAGE,
+        NEW_DAY_USAGE, SUBMIT_USAGE, USAGE,
     },
-    utils::Puzzle,
+    fetch::{self, Curl, Fetcher},
+    generate::{self, Rng},
+    history::{self, Entry, History},
+    journey::{bench_day, load_input, run_day},
+    registry::{self, Day},
+    report::{self, Format, RunRecord},
+    scaffold,
+    submit::{self, AocServer, Feedback},
+    trace,
+    utils::{run_parallel, Puzzle, Stats, Task},
+    watch::Watcher,
 };
 
-fn main() {
-    let track_list = vec![
-        Puzzle::run("sonar_deep", Box::new(&run_sonar_deep)),
-        Puzzle::run("dive", Box::new(&run_dive)),
-        Puzzle::run("binary_diagnostic", Box::new(&run_binary_diagnostic)),
-        Puzzle::run("giant_squid", Box::new(&run_giant_squid)),
-        Puzzle::run("hydrothremal_venture", Box::new(&run_hydrothermal_venture)),
-    ];
-
-    println!("");
-
-    track_list.iter().enumerate().for_each(|(i, puzzle)| {
-        println!(
-            "Puzzle {0} [{1:^30}] elapsed {2:>7} us",
-            i,
-            puzzle.name(),
-            puzzle.elapsed_time().as_micros()
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: advent_of_code_rust::memory::CountingAllocator =
+    advent_of_code_rust::memory::CountingAllocator;
+
+fn print_stats(label: &str, stats: &Stats) {
+    println!(
+        "    {0:<6} min {1:>9.1} us  median {2:>9.1} us  mean {3:>9.1} us  p95 {4:>9.1} us  stddev {5:>8.1} us",
+        label,
+        stats.min.as_secs_f64() * 1e6,
+        stats.median.as_secs_f64() * 1e6,
+        stats.mean.as_secs_f64() * 1e6,
+        stats.p95.as_secs_f64() * 1e6,
+        stats.stddev.as_secs_f64() * 1e6
+    );
+}
+
+fn run_bench(options: &Options, days: &[Day], inputs_dir: &Path, iterations: usize) {
+    for (i, day) in days.iter().enumerate() {
+        let input_path = options.input_path(day, inputs_dir);
+        println!("Puzzle {0} [{1:^30}] {2} runs", i, day.name, iterations);
+        match bench_day(day, &input_path, options.warmup, iterations) {
+            Ok(result) => {
+                print_stats("parse", &result.parse);
+                print_stats("part 1", &result.part1);
+                print_stats("part 2", &result.part2);
+            }
+            Err(err) => eprintln!("  error: {}", err),
+        }
+    }
+}
+
+fn run_new_day(args: &[String]) {
+    let new_day = match NewDay::parse(args) {
+        Ok(n) => n,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, NEW_DAY_USAGE);
+            process::exit(2);
+        }
+    };
+    if new_day.help {
+        println!("{}", NEW_DAY_USAGE);
+        return;
+    }
+    match scaffold::new_day(Path::new("."), &new_day) {
+        Ok(touched) => {
+            for path in touched {
+                println!("  {}", path.display());
+            }
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+// Answers are reloaded on every run, since editing them is one of the changes
+// being watched for.
+fn run_watch(options: &Options, day: &Day, inputs_dir: &Path, answers_path: &Path) -> ! {
+    let input_path = options.input_path(day, inputs_dir);
+    let mut watcher = Watcher::new(vec![input_path.clone(), answers_path.to_path_buf()]);
+    loop {
+        let answers = Answers::from_file_or_empty(answers_path).unwrap_or_else(|err| {
+            eprintln!(
+                "error: cannot load answers {}: {}",
+                answers_path.display(),
+                err
+            );
+            Answers::default()
+        });
+        let (d, part, path) = (*day, options.part, input_path.clone());
+        let task: Task<_> =
+            Box::new(move || run_day(&d, part, &path, &answers).map_err(|e| e.to_string()));
+        let puzzle = match options.timeout {
+            Some(timeout) => Puzzle::run_with_timeout(day.name, task, timeout),
+            None => Puzzle::run(day.name, task),
+        };
+        let records = [RunRecord::new(day, &puzzle)];
+        if options.format == Format::Table {
+            report::write_day(&mut io::stdout(), &records[0]).unwrap();
+        }
+        report::write(&mut io::stdout(), options.format, &records).unwrap();
+        let paths: Vec<String> = watcher
+            .paths()
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        eprintln!(
+            "\nWatching {} for changes, Ctrl-C to stop",
+            paths.join(" and ")
+        );
+        watcher.wait(Duration::from_millis(500));
+    }
+}
+
+// Compares against the history before appending to it, so a run is never its
+// own baseline. Returns whether any day got slower than the threshold.
+fn track_history(options: &Options, records: &[RunRecord]) -> bool {
+    let path = options.history_path();
+    let mut regressed = false;
+    if options.compare {
+        let history = match History::from_file_or_empty(&path) {
+            Ok(h) => h,
+            Err(err) => {
+                eprintln!("error: cannot load history {}: {}", path.display(), err);
+                process::exit(2);
+            }
+        };
+        let deltas = history::compare(&history, records);
+        let result = if options.format == Format::Table {
+            history::write_deltas(&mut io::stdout(), &deltas, options.threshold)
+        } else {
+            history::write_deltas(&mut io::stderr(), &deltas, options.threshold)
+        };
+        result.unwrap();
+        regressed = deltas.iter().any(|d| d.regressed(options.threshold));
+    }
+    if options.record {
+        let run = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let entries: Vec<Entry> = records
+            .iter()
+            .filter_map(|r| Entry::from_record(run, r))
+            .collect();
+        if let Err(err) = History::append(&path, &entries) {
+            eprintln!("error: cannot write history {}: {}", path.display(), err);
+            process::exit(2);
+        }
+    }
+    regressed
+}
+
+fn run_generate(args: &[String]) {
+    let options = match GenerateInput::parse(args) {
+        Ok(o) => o,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, GENERATE_USAGE);
+            process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", GENERATE_USAGE);
+        return;
+    }
+    let generator = match generate::find(options.year, options.day) {
+        Some(g) => g,
+        None => {
+            eprintln!(
+                "error: no generator for day {} of {}",
+                options.day, options.year
+            );
+            process::exit(2);
+        }
+    };
+    let size = options.size.unwrap_or(generator.default_size);
+    let input = (generator.generate)(&mut Rng::new(options.seed), size);
+    io::stdout().write_all(input.as_bytes()).unwrap();
+}
+
+fn run_fetch(args: &[String]) {
+    let options = match FetchInputs::parse(args) {
+        Ok(o) => o,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, FETCH_USAGE);
+            process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", FETCH_USAGE);
+        return;
+    }
+    let session = match fetch::session(env::var_os("AOC_SESSION"), &options.session_file) {
+        Ok(s) => s,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(2);
+        }
+    };
+    let inputs_dir = options.inputs_dir(env::var_os("AOC_INPUTS_DIR"));
+    let mut fetcher = Fetcher::new(Curl, &session, &inputs_dir);
+    let (first, last) = options.days;
+    for day in first..=last {
+        match fetcher.fetch(options.year, day) {
+            Ok(fetched) if fetched.cached => println!("  {} (cached)", fetched.path.display()),
+            Ok(fetched) => println!("  {}", fetched.path.display()),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+// The day's own answer for the part, when none is given on the command line.
+fn solve(options: &SubmitAnswer, inputs_dir: &Path) -> Result<String, Box<dyn Error>> {
+    let day = registry::find(options.year, options.day).ok_or_else(|| {
+        format!(
+            "day {} of {} is not implemented, give the answer instead",
+            options.day, options.year
         )
-    })
+    })?;
+    let input_path = match &options.input {
+        Some(path) => path.clone(),
+        None => day.input_path(inputs_dir),
+    };
+    let solution = (day.parse)(&load_input(&input_path)?)?;
+    let answer = match options.part {
+        1 => solution.part1(),
+        _ => solution.part2(),
+    };
+    Ok(answer.to_string())
+}
+
+fn run_submit(args: &[String]) {
+    let options = match SubmitAnswer::parse(args) {
+        Ok(o) => o,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, SUBMIT_USAGE);
+            process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", SUBMIT_USAGE);
+        return;
+    }
+    let inputs_dir = options.inputs_dir(env::var_os("AOC_INPUTS_DIR"));
+    let answer = match &options.answer {
+        Some(answer) => answer.clone(),
+        None => match solve(&options, &inputs_dir) {
+            Ok(answer) => answer,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            }
+        },
+    };
+    let session = match fetch::session(env::var_os("AOC_SESSION"), &options.session_file) {
+        Ok(s) => s,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(2);
+        }
+    };
+    let mut server = AocServer::new(Curl, &session);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let target = (options.year, options.day, options.part);
+    let feedback = match submit::submit(&mut server, &options.ledger, target, &answer, now) {
+        Ok(feedback) => feedback,
+        Err(err) => {
+            eprintln!("error: not sent: {}", err);
+            process::exit(1);
+        }
+    };
+    let label = format!(
+        "{} day {} part {}: {}",
+        options.year, options.day, options.part, answer
+    );
+    match feedback {
+        Feedback::Correct => {
+            println!("{} is right", label);
+            let answers_path = registry::year_dir(&inputs_dir, options.year).join("answers.txt");
+            let known = Answers::from_file_or_empty(&answers_path)
+                .is_ok_and(|a| a.expected(options.day, options.part).is_some());
+            if !known {
+                if let Err(err) = Answers::append(&answers_path, options.day, options.part, &answer)
+                {
+                    eprintln!(
+                        "error: cannot write answers {}: {}",
+                        answers_path.display(),
+                        err
+                    );
+                }
+            }
+        }
+        Feedback::TooHigh => println!("{} is too high", label),
+        Feedback::TooLow => println!("{} is too low", label),
+        Feedback::Wrong => println!("{} is wrong", label),
+        Feedback::Wait(left) => println!(
+            "{} was not judged, answered too recently; wait {}s",
+            label,
+            left.as_secs()
+        ),
+        Feedback::WrongLevel => println!(
+            "{} was not judged, the part is already solved or not open yet",
+            label
+        ),
+    }
+    if feedback != Feedback::Correct {
+        process::exit(1);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(|a| a.as_str()) {
+        Some("new-day") => return run_new_day(&args[1..]),
+        Some("generate") => return run_generate(&args[1..]),
+        Some("fetch") => return run_fetch(&args[1..]),
+        Some("submit") => return run_submit(&args[1..]),
+        _ => {}
+    }
+    let options = match Options::parse(&args) {
+        Ok(o) => o,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+    let registry = registry::days();
+    for (day, level) in options.traced(&registry) {
+        trace::enable(day, level);
+    }
+    let days = match options.select(&registry) {
+        Ok(d) => d,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(2);
+        }
+    };
+
+    let inputs_dir = options.inputs_dir(env::var_os("AOC_INPUTS_DIR"));
+
+    if let Some(iterations) = options.bench {
+        run_bench(&options, &days, &inputs_dir, iterations);
+        return;
+    }
+
+    let year = options.year(&registry);
+    let answers_path = options
+        .answers
+        .clone()
+        .unwrap_or_else(|| registry::year_dir(&inputs_dir, year).join("answers.txt"));
+
+    if options.watch {
+        run_watch(&options, &days[0], &inputs_dir, &answers_path);
+    }
+    // The default answers belong to the default inputs, not to one passed
+    // with --input.
+    let answers = match (&options.answers, &options.input) {
+        (Some(path), _) => Answers::from_file(path),
+        (None, Some(_)) => Ok(Answers::default()),
+        (None, None) => Answers::from_file_or_empty(&answers_path),
+    };
+    let answers = match answers {
+        Ok(a) => Arc::new(a),
+        Err(err) => {
+            eprintln!(
+                "error: cannot load answers {}: {}",
+                answers_path.display(),
+                err
+            );
+            process::exit(2);
+        }
+    };
+
+    let wall_time = Instant::now();
+    let tasks = days.iter().map(|day| {
+        let day = *day;
+        let input_path = options.input_path(&day, &inputs_dir);
+        let part = options.part;
+        let answers = Arc::clone(&answers);
+        let task: Task<_> =
+            Box::new(move || run_day(&day, part, &input_path, &answers).map_err(|e| e.to_string()));
+        (day.name.to_string(), task)
+    });
+
+    let mut records = vec![];
+    let mut record_day = |day: &Day, puzzle: &Puzzle<_>| {
+        let record = RunRecord::new(day, puzzle);
+        if options.format == Format::Table {
+            report::write_day(&mut io::stdout(), &record).unwrap();
+        }
+        records.push(record);
+    };
+    if options.jobs > 1 {
+        let puzzles = run_parallel(tasks.collect(), options.jobs, options.timeout);
+        for (day, puzzle) in days.iter().zip(&puzzles) {
+            record_day(day, puzzle);
+        }
+    } else {
+        for (day, (name, task)) in days.iter().zip(tasks) {
+            let puzzle = match options.timeout {
+                Some(timeout) => Puzzle::run_with_timeout(&name, task, timeout),
+                None => Puzzle::run(&name, task),
+            };
+            record_day(day, &puzzle);
+        }
+    }
+    let wall_time = wall_time.elapsed();
+
+    report::write(&mut io::stdout(), options.format, &records).unwrap();
+    if options.format == Format::Table {
+        println!("Total wall time {:>7} us", wall_time.as_micros());
+    }
+
+    let regressed = track_history(&options, &records);
+    if regressed || records.iter().any(|r| r.failed()) {
+        process::exit(1);
+    }
 }
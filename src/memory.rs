@@ -0,0 +1,179 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+// What a puzzle asked of the allocator while it ran on its thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    // Every byte allocated, including what was freed again.
+    pub bytes: u64,
+    // Most bytes live at once, above what was live when the puzzle started.
+    pub peak: u64,
+    pub count: u64,
+}
+
+// The system allocator, counting per thread. Installed by the binary with the
+// `alloc-stats` feature, and in the library's own tests; without it `measure`
+// reports nothing.
+pub struct CountingAllocator;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+struct Counters {
+    bytes: Cell<u64>,
+    count: Cell<u64>,
+    live: Cell<u64>,
+    peak: Cell<u64>,
+}
+
+thread_local! {
+    // Const and without a destructor, so touching it never allocates.
+    static COUNTERS: Counters = const {
+        Counters {
+            bytes: Cell::new(0),
+            count: Cell::new(0),
+            live: Cell::new(0),
+            peak: Cell::new(0),
+        }
+    };
+}
+
+// A realloc counts as a new allocation of `allocated` bytes that frees the
+// old `freed` ones.
+fn record(allocated: usize, freed: usize) {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        INSTALLED.store(true, Ordering::Relaxed);
+    }
+    let _ = COUNTERS.try_with(|c| {
+        if allocated > 0 {
+            c.bytes.set(c.bytes.get() + allocated as u64);
+            c.count.set(c.count.get() + 1);
+        }
+        // Memory freed by another thread than the one that allocated it can
+        // take this thread's count below zero.
+        let live = (c.live.get() + allocated as u64).saturating_sub(freed as u64);
+        c.live.set(live);
+        c.peak.set(c.peak.get().max(live));
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+pub fn installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+// Runs `f` and reports what it allocated on this thread; None when the
+// counting allocator is not installed.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<AllocStats>) {
+    if !installed() {
+        return (f(), None);
+    }
+    let snapshot = || COUNTERS.with(|c| (c.bytes.get(), c.count.get(), c.live.get(), c.peak.get()));
+    let (bytes, count, live, peak) = snapshot();
+    COUNTERS.with(|c| c.peak.set(live));
+    let result = f();
+    let (end_bytes, end_count, _, end_peak) = snapshot();
+    // An enclosing `measure` still sees the highest peak of the two.
+    COUNTERS.with(|c| c.peak.set(end_peak.max(peak)));
+    let stats = AllocStats {
+        bytes: end_bytes - bytes,
+        peak: end_peak - live,
+        count: end_count - count,
+    };
+    (result, Some(stats))
+}
+
+// "512 B", "12.3 KiB", "4.5 MiB"
+pub struct Bytes(pub u64);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = ["B", "KiB", "MiB", "GiB"];
+        let mut value = self.0 as f64;
+        let mut unit = 0;
+        while value >= 1024.0 && unit + 1 < units.len() {
+            value /= 1024.0;
+            unit += 1;
+        }
+        match unit {
+            0 => write!(f, "{} B", self.0),
+            _ => write!(f, "{:.1} {}", value, units[unit]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hint::black_box;
+
+    use crate::memory::{self, Bytes, CountingAllocator};
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_measure() {
+        assert!(memory::installed());
+        let (v, stats) = memory::measure(|| {
+            let mut v: Vec<u64> = Vec::with_capacity(1000);
+            v.push(1);
+            black_box(vec![0_u8; 100]);
+            v
+        });
+        let stats = stats.unwrap();
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.bytes, 8100);
+        assert_eq!(stats.peak, 8100);
+        drop(v);
+
+        let ((), outer) = memory::measure(|| {
+            let ((), inner) = memory::measure(|| {
+                black_box(vec![0_u8; 4000]);
+            });
+            assert_eq!(inner.unwrap().peak, 4000);
+            black_box(vec![0_u8; 1000]);
+        });
+        let outer = outer.unwrap();
+        assert_eq!((outer.bytes, outer.peak, outer.count), (5000, 4000, 2));
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(Bytes(512).to_string(), "512 B");
+        assert_eq!(Bytes(12595).to_string(), "12.3 KiB");
+        assert_eq!(Bytes(3 << 20).to_string(), "3.0 MiB");
+    }
+}
@@ -0,0 +1,313 @@
+use std::str::FromStr;
+
+use crate::{d00_aoc::ParseError, grid::Point};
+
+// Helpers for the input shapes that keep coming back. Each takes the day and
+// the 1-based line number so its errors point at the offending token, and
+// works on a single line so it can be fed from `for_each_line` as well.
+
+// Lines without their ending, numbered from 1. A trailing newline does not
+// add an empty line, and "\r\n" endings lose their '\r'.
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let input = input.strip_suffix('\n').unwrap_or(input);
+    let lines = if input.is_empty() {
+        None
+    } else {
+        Some(input.split('\n'))
+    };
+    lines
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.strip_suffix('\r').unwrap_or(line)))
+}
+
+// `token` must be a subslice of `line`; surrounding whitespace is ignored.
+pub fn int<T: FromStr>(
+    day: u8,
+    line_number: usize,
+    line: &str,
+    token: &str,
+) -> Result<T, ParseError> {
+    let token = token.trim();
+    token
+        .parse()
+        .map_err(|_| ParseError::at(day, line_number, line, token, "an integer"))
+}
+
+pub fn int_line<T: FromStr>(day: u8, line_number: usize, line: &str) -> Result<T, ParseError> {
+    int(day, line_number, line, line)
+}
+
+// One integer per line.
+pub fn ints<T: FromStr>(day: u8, input: &str) -> Result<Vec<T>, ParseError> {
+    lines(input)
+        .map(|(n, line)| int_line(day, n, line))
+        .collect()
+}
+
+// "7,4,9,5"
+pub fn comma_list<T: FromStr>(
+    day: u8,
+    line_number: usize,
+    line: &str,
+) -> Result<Vec<T>, ParseError> {
+    line.split(',')
+        .map(|token| int(day, line_number, line, token))
+        .collect()
+}
+
+// "22 13 17 11  0"
+pub fn whitespace_list<T: FromStr>(
+    day: u8,
+    line_number: usize,
+    line: &str,
+) -> Result<Vec<T>, ParseError> {
+    line.split_whitespace()
+        .map(|token| int(day, line_number, line, token))
+        .collect()
+}
+
+// Numbered lines grouped on blank lines; runs of blank lines and blank lines
+// at either end do not make empty blocks.
+pub fn blocks(input: &str) -> Vec<Vec<(usize, &str)>> {
+    let mut blocks = vec![];
+    let mut block = vec![];
+    for (n, line) in lines(input) {
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                blocks.push(block);
+                block = vec![];
+            }
+        } else {
+            block.push((n, line));
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+    blocks
+}
+
+// Rows of whitespace-separated numbers, all as long as the first.
+pub fn number_grid<T: FromStr>(
+    day: u8,
+    block: &[(usize, &str)],
+) -> Result<Vec<Vec<T>>, ParseError> {
+    let mut rows: Vec<Vec<T>> = vec![];
+    for &(n, line) in block {
+        let row = whitespace_list(day, n, line)?;
+        if rows.first().is_some_and(|first| first.len() != row.len()) {
+            return Err(ParseError::at(
+                day,
+                n,
+                line,
+                line,
+                "a row as long as the first",
+            ));
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+// "10110" in base 2, "2199943210" in base 10.
+pub fn digits(day: u8, line_number: usize, line: &str, radix: u32) -> Result<Vec<u32>, ParseError> {
+    let line = line.trim_end();
+    let expected = digit_name(radix);
+    line.char_indices()
+        .map(|(i, c)| {
+            c.to_digit(radix).ok_or_else(|| {
+                ParseError::at(day, line_number, line, &line[i..i + c.len_utf8()], expected)
+            })
+        })
+        .collect()
+}
+
+fn digit_name(radix: u32) -> &'static str {
+    if radix == 2 {
+        "a binary digit"
+    } else {
+        "a digit"
+    }
+}
+
+// A row of a digit grid, not empty and `width` digits wide when given.
+pub fn digit_row(
+    day: u8,
+    line_number: usize,
+    line: &str,
+    radix: u32,
+    width: Option<usize>,
+) -> Result<Vec<u32>, ParseError> {
+    let row = digits(day, line_number, line, radix)?;
+    if row.is_empty() {
+        return Err(ParseError::at(
+            day,
+            line_number,
+            line,
+            line,
+            digit_name(radix),
+        ));
+    }
+    if width.is_some_and(|w| w != row.len()) {
+        return Err(ParseError::at(
+            day,
+            line_number,
+            line,
+            line,
+            "a row as wide as the first",
+        ));
+    }
+    Ok(row)
+}
+
+// Digit rows, all as wide as the first.
+pub fn digit_grid(day: u8, input: &str, radix: u32) -> Result<Vec<Vec<u32>>, ParseError> {
+    let mut rows: Vec<Vec<u32>> = vec![];
+    for (n, line) in lines(input) {
+        let width = rows.first().map(|first| first.len());
+        rows.push(digit_row(day, n, line, radix, width)?);
+    }
+    Ok(rows)
+}
+
+// "0,9 -> 5,9"
+pub fn segment(day: u8, line_number: usize, line: &str) -> Result<(Point, Point), ParseError> {
+    let error = |token: &str, expected| ParseError::at(day, line_number, line, token, expected);
+    let point = |p: &str| -> Result<Point, ParseError> {
+        let (x, y) = p.split_once(',').ok_or_else(|| error(p, "a point x,y"))?;
+        Ok(Point::new(
+            int(day, line_number, line, x)?,
+            int(day, line_number, line, y)?,
+        ))
+    };
+    let (p1, p2) = line
+        .split_once(" -> ")
+        .ok_or_else(|| error(line, "a segment x1,y1 -> x2,y2"))?;
+    Ok((point(p1)?, point(p2)?))
+}
+
+// "forward 5"; the word is left to the caller to interpret.
+pub fn command<T: FromStr>(
+    day: u8,
+    line_number: usize,
+    line: &str,
+) -> Result<(&str, T), ParseError> {
+    let error = |token, expected| ParseError::at(day, line_number, line, token, expected);
+    let mut tokens = line.split_whitespace();
+    let (word, value) = match (tokens.next(), tokens.next()) {
+        (Some(word), Some(value)) => (word, value),
+        (Some(_), None) => return Err(error(&line[line.len()..], "a value")),
+        (None, _) => return Err(error(line, "a command")),
+    };
+    if let Some(extra) = tokens.next() {
+        return Err(error(extra, "end of line"));
+    }
+    Ok((word, int(day, line_number, line, value)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{grid::Point, parse};
+
+    #[test]
+    fn test_lines() {
+        let lines: Vec<(usize, &str)> = parse::lines("a\r\nb\n\nc\r\n").collect();
+        assert_eq!(lines, vec![(1, "a"), (2, "b"), (3, ""), (4, "c")]);
+        assert_eq!(parse::lines("").count(), 0);
+        assert_eq!(parse::lines("a\n\n").count(), 2);
+    }
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(
+            parse::ints::<i32>(1, "199\r\n-200\n 208 \n").unwrap(),
+            vec![199, -200, 208]
+        );
+        let err = parse::ints::<i32>(1, "199\n2x0\n").unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (1, 2, 1));
+        assert_eq!(err.text, "2x0");
+        let err = parse::ints::<u8>(1, "300").unwrap_err();
+        assert_eq!(err.expected, "an integer");
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(
+            parse::comma_list::<i32>(4, 1, "7,4, 9").unwrap(),
+            vec![7, 4, 9]
+        );
+        let err = parse::comma_list::<i32>(4, 1, "7,4,x9").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (5, "x9"));
+        assert_eq!(
+            parse::whitespace_list::<i32>(4, 3, " 8  2 23").unwrap(),
+            vec![8, 2, 23]
+        );
+        let err = parse::whitespace_list::<i32>(4, 3, "9 18 1x").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 6));
+    }
+
+    #[test]
+    fn test_blocks() {
+        let blocks = parse::blocks("7,4\n\n1 2\n3 4\r\n\n\n5 6\n");
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[1], vec![(3, "1 2"), (4, "3 4")]);
+        assert_eq!(blocks[2], vec![(7, "5 6")]);
+        assert_eq!(
+            parse::number_grid::<i32>(4, &blocks[1]).unwrap(),
+            vec![vec![1, 2], vec![3, 4]]
+        );
+        let err = parse::number_grid::<i32>(4, &[(3, "1 2"), (4, "3")]).unwrap_err();
+        assert_eq!((err.line, err.expected), (4, "a row as long as the first"));
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(
+            parse::digits(3, 1, "10110\r", 2).unwrap(),
+            vec![1, 0, 1, 1, 0]
+        );
+        let err = parse::digits(3, 2, "11210", 2).unwrap_err();
+        assert_eq!(
+            (err.column, err.text.as_str(), err.expected),
+            (3, "2", "a binary digit")
+        );
+        let grid = parse::digit_grid(9, "219\n398\n", 10).unwrap();
+        assert_eq!(grid, vec![vec![2, 1, 9], vec![3, 9, 8]]);
+        let err = parse::digit_grid(9, "219\n39\n", 10).unwrap_err();
+        assert_eq!((err.line, err.text.as_str()), (2, "39"));
+        let err = parse::digit_grid(9, "219\n\n398", 10).unwrap_err();
+        assert_eq!((err.line, err.expected), (2, "a digit"));
+        let err = parse::digit_row(3, 4, "1011", 2, Some(5)).unwrap_err();
+        assert_eq!((err.line, err.expected), (4, "a row as wide as the first"));
+    }
+
+    #[test]
+    fn test_segment() {
+        let (p1, p2) = parse::segment(5, 1, "0,9 -> 5,9").unwrap();
+        assert_eq!((p1, p2), (Point::new(0, 9), Point::new(5, 9)));
+        let err = parse::segment(5, 2, "8,0 -> 0,y").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (10, "y"));
+        let err = parse::segment(5, 2, "8,0 => 0,8").unwrap_err();
+        assert_eq!(err.expected, "a segment x1,y1 -> x2,y2");
+        let err = parse::segment(5, 1, "0,9 -> 59").unwrap_err();
+        assert_eq!((err.column, err.expected), (8, "a point x,y"));
+    }
+
+    #[test]
+    fn test_command() {
+        assert_eq!(
+            parse::command::<i32>(2, 1, "forward 5").unwrap(),
+            ("forward", 5)
+        );
+        let err = parse::command::<i32>(2, 1, "down x5").unwrap_err();
+        assert_eq!((err.column, err.expected), (6, "an integer"));
+        let err = parse::command::<i32>(2, 1, "up").unwrap_err();
+        assert_eq!((err.column, err.expected), (3, "a value"));
+        let err = parse::command::<i32>(2, 1, "up 1 2").unwrap_err();
+        assert_eq!((err.column, err.expected), (6, "end of line"));
+        let err = parse::command::<i32>(2, 1, "").unwrap_err();
+        assert_eq!(err.expected, "a command");
+    }
+}
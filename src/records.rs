@@ -0,0 +1,70 @@
+use std::{error::Error, fs, io, path::Path};
+
+// The plain text files the runner keeps next to the inputs: one record per
+// line as whitespace-separated fields, with blank lines and '#' comments
+// skipped. `record` turns the fields of a line into a record, or says what it
+// expected; `file` names the file in the error.
+pub fn parse<T>(
+    input_str: &str,
+    file: &str,
+    mut record: impl FnMut(&[&str]) -> Result<T, String>,
+) -> Result<Vec<T>, Box<dyn Error>> {
+    let mut records = vec![];
+    for (i, line) in input_str.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        match record(&fields) {
+            Ok(r) => records.push(r),
+            Err(expected) => return Err(format!("{} line {}: {}", file, i + 1, expected).into()),
+        }
+    }
+    Ok(records)
+}
+
+// The file's content, empty when it does not exist yet.
+pub fn read_or_empty(path: &Path) -> Result<String, Box<dyn Error>> {
+    match fs::read_to_string(path) {
+        Ok(input_str) => Ok(input_str),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(err) => Err(Box::new(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::records;
+
+    fn pair(fields: &[&str]) -> Result<(u8, String), String> {
+        match fields {
+            [n, word] => match n.parse() {
+                Ok(n) => Ok((n, word.to_string())),
+                Err(_) => Err("invalid number".to_string()),
+            },
+            _ => Err("expected 'n word'".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let records = records::parse("# n word\n1 a\n\n  2   b  \n", "pairs", pair).unwrap();
+        assert_eq!(records, vec![(1, "a".to_string()), (2, "b".to_string())]);
+        let err = records::parse("1 a\n# x\nb 2", "pairs", pair).unwrap_err();
+        assert_eq!(err.to_string(), "pairs line 3: invalid number");
+        let err = records::parse("1 a b", "pairs", pair).unwrap_err();
+        assert_eq!(err.to_string(), "pairs line 1: expected 'n word'");
+    }
+
+    #[test]
+    fn test_read_or_empty() {
+        assert_eq!(
+            records::read_or_empty(Path::new("no/such/file")).unwrap(),
+            ""
+        );
+        assert!(records::read_or_empty(Path::new("src")).is_err());
+    }
+}
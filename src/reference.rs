@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+
+use crate::d00_aoc::Answer;
+
+// Deliberately naive solvers, written straight from the puzzle text, to check
+// the optimized ones against. They assume well-formed input and panic
+// otherwise.
+pub type Solve = fn(&str) -> (Answer, Answer);
+
+#[derive(Clone, Copy)]
+pub struct Reference {
+    pub year: u16,
+    pub day: u8,
+    pub solve: Solve,
+}
+
+pub fn references() -> Vec<Reference> {
+    vec![
+        Reference {
+            year: 2021,
+            day: 1,
+            solve: sonar_deep,
+        },
+        Reference {
+            year: 2021,
+            day: 2,
+            solve: dive,
+        },
+        Reference {
+            year: 2021,
+            day: 3,
+            solve: binary_diagnostic,
+        },
+        Reference {
+            year: 2021,
+            day: 4,
+            solve: giant_squid,
+        },
+        Reference {
+            year: 2021,
+            day: 5,
+            solve: hydrothermal_venture,
+        },
+    ]
+}
+
+pub fn find(year: u16, day: u8) -> Option<Reference> {
+    references()
+        .into_iter()
+        .find(|r| r.year == year && r.day == day)
+}
+
+fn increases(values: &[i64]) -> usize {
+    (1..values.len())
+        .filter(|&i| values[i] > values[i - 1])
+        .count()
+}
+
+pub fn sonar_deep(input: &str) -> (Answer, Answer) {
+    let depths: Vec<i64> = input.lines().map(|l| l.trim().parse().unwrap()).collect();
+    let sums: Vec<i64> = depths.windows(3).map(|w| w.iter().sum()).collect();
+    (increases(&depths).into(), increases(&sums).into())
+}
+
+pub fn dive(input: &str) -> (Answer, Answer) {
+    let (mut position, mut depth, mut aim, mut aimed_depth) = (0_i64, 0_i64, 0_i64, 0_i64);
+    for line in input.lines() {
+        let (command, value) = line.split_once(' ').unwrap();
+        let value: i64 = value.trim().parse().unwrap();
+        match command {
+            "forward" => {
+                position += value;
+                aimed_depth += aim * value;
+            }
+            "down" => {
+                depth += value;
+                aim += value;
+            }
+            "up" => {
+                depth -= value;
+                aim -= value;
+            }
+            _ => panic!("unknown command {}", command),
+        }
+    }
+    ((position * depth).into(), (position * aimed_depth).into())
+}
+
+fn ones(numbers: &[&str], bit: usize) -> usize {
+    numbers.iter().filter(|n| n.as_bytes()[bit] == b'1').count()
+}
+
+// Filters bit by bit until one number is left. A filter that would drop every
+// number keeps them all instead.
+fn rating(numbers: &[&str], keep_most_common: bool) -> i64 {
+    let mut numbers = numbers.to_vec();
+    let mut bit = 0;
+    while numbers.len() > 1 && bit < numbers[0].len() {
+        let ones = ones(&numbers, bit);
+        let zeros = numbers.len() - ones;
+        let keep = match (keep_most_common, ones >= zeros) {
+            (true, true) | (false, false) => b'1',
+            _ => b'0',
+        };
+        let kept: Vec<&str> = numbers
+            .iter()
+            .copied()
+            .filter(|n| n.as_bytes()[bit] == keep)
+            .collect();
+        if !kept.is_empty() {
+            numbers = kept;
+        }
+        bit += 1;
+    }
+    i64::from_str_radix(numbers[0], 2).unwrap()
+}
+
+pub fn binary_diagnostic(input: &str) -> (Answer, Answer) {
+    let numbers: Vec<&str> = input.lines().map(|l| l.trim()).collect();
+    let width = numbers[0].len();
+    let (mut gamma, mut epsilon) = (0, 0);
+    for bit in 0..width {
+        let ones = ones(&numbers, bit);
+        gamma *= 2;
+        epsilon *= 2;
+        if ones > numbers.len() - ones {
+            gamma += 1;
+        } else {
+            epsilon += 1;
+        }
+    }
+    let part2 = rating(&numbers, true) * rating(&numbers, false);
+    ((gamma * epsilon).into(), part2.into())
+}
+
+fn wins(marked: &[[bool; 5]; 5]) -> bool {
+    (0..5).any(|i| (0..5).all(|j| marked[i][j]) || (0..5).all(|j| marked[j][i]))
+}
+
+// Marks every board after every draw and scans all of them for a full row or
+// column. Boards completing on the same draw win in input order.
+pub fn giant_squid(input: &str) -> (Answer, Answer) {
+    let mut lines = input.lines();
+    let draws: Vec<i64> = lines
+        .next()
+        .unwrap()
+        .split(',')
+        .map(|n| n.trim().parse().unwrap())
+        .collect();
+    let rows: Vec<Vec<i64>> = lines
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.split_whitespace().map(|n| n.parse().unwrap()).collect())
+        .collect();
+    let boards: Vec<&[Vec<i64>]> = rows.chunks(5).collect();
+    let mut marked = vec![[[false; 5]; 5]; boards.len()];
+    let mut won = vec![false; boards.len()];
+    let mut scores = vec![];
+    for &draw in &draws {
+        for (b, board) in boards.iter().enumerate() {
+            for i in 0..5 {
+                for j in 0..5 {
+                    if board[i][j] == draw {
+                        marked[b][i][j] = true;
+                    }
+                }
+            }
+        }
+        for (b, board) in boards.iter().enumerate() {
+            if !won[b] && wins(&marked[b]) {
+                won[b] = true;
+                let mut unmarked = 0;
+                for i in 0..5 {
+                    for j in 0..5 {
+                        if !marked[b][i][j] {
+                            unmarked += board[i][j];
+                        }
+                    }
+                }
+                scores.push(unmarked * draw);
+            }
+        }
+    }
+    (scores[0].into(), scores[scores.len() - 1].into())
+}
+
+pub fn hydrothermal_venture(input: &str) -> (Answer, Answer) {
+    let mut straight: HashMap<(i64, i64), usize> = HashMap::new();
+    let mut all: HashMap<(i64, i64), usize> = HashMap::new();
+    for line in input.lines() {
+        let points: Vec<i64> = line
+            .split(" -> ")
+            .flat_map(|p| p.split(','))
+            .map(|n| n.trim().parse().unwrap())
+            .collect();
+        let (x1, y1, x2, y2) = (points[0], points[1], points[2], points[3]);
+        let (dx, dy) = ((x2 - x1).signum(), (y2 - y1).signum());
+        let length = (x2 - x1).abs().max((y2 - y1).abs());
+        if dx != 0 && dy != 0 && (x2 - x1).abs() != (y2 - y1).abs() {
+            continue;
+        }
+        for step in 0..=length {
+            let point = (x1 + dx * step, y1 + dy * step);
+            if dx == 0 || dy == 0 {
+                *straight.entry(point).or_insert(0) += 1;
+            }
+            *all.entry(point).or_insert(0) += 1;
+        }
+    }
+    let overlaps = |vents: &HashMap<(i64, i64), usize>| vents.values().filter(|&&n| n > 1).count();
+    (overlaps(&straight).into(), overlaps(&all).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        d00_aoc::Answer,
+        generate::{self, Rng},
+        reference::{find, references, Reference},
+        registry::{self, Day},
+        utils::{Puzzle, Status},
+    };
+
+    const SEED: u64 = 2021;
+    const CASES: usize = 1000;
+    const MAX_SIZE: i64 = 30;
+    // Far past the size of the real inputs, where overflows show. The naive
+    // references bound how far that can go.
+    const LARGE_CASES: u64 = 2;
+    const LARGE_FACTOR: usize = 50;
+
+    fn solve(day: &Day, input: &str) -> Result<(Answer, Answer), String> {
+        let puzzle = Puzzle::run(
+            day.name,
+            Box::new(|| {
+                let solution = (day.parse)(input).map_err(|e| e.to_string())?;
+                Ok((solution.part1(), solution.part2()))
+            }),
+        );
+        match puzzle.status() {
+            Status::Done(result) => result.clone(),
+            Status::Panicked(msg) => Err(format!("panicked: {}", msg)),
+            Status::TimedOut(_) => unreachable!(),
+        }
+    }
+
+    // None when both agree, or when the reference itself rejects the input,
+    // as happens to most of the candidates while shrinking.
+    fn disagreement(day: &Day, reference: &Reference, input: &str) -> Option<String> {
+        let expected = Puzzle::run("reference", Box::new(|| (reference.solve)(input)));
+        let expected = match expected.status() {
+            Status::Done(answers) => answers.clone(),
+            _ => return None,
+        };
+        match solve(day, input) {
+            Ok(actual) if actual == expected => None,
+            Ok((part1, part2)) => Some(format!(
+                "got {} and {}, expected {} and {}",
+                part1, part2, expected.0, expected.1
+            )),
+            Err(err) => Some(format!(
+                "{}, expected {} and {}",
+                err, expected.0, expected.1
+            )),
+        }
+    }
+
+    // Drops lines for as long as the input keeps failing.
+    fn shrink(day: &Day, reference: &Reference, input: &str) -> String {
+        let mut lines: Vec<&str> = input.lines().collect();
+        let mut i = 0;
+        while i < lines.len() {
+            let mut candidate = lines.clone();
+            candidate.remove(i);
+            let candidate_input = candidate.join("\n") + "\n";
+            if disagreement(day, reference, &candidate_input).is_some() {
+                lines = candidate;
+            } else {
+                i += 1;
+            }
+        }
+        lines.join("\n") + "\n"
+    }
+
+    #[test]
+    fn test_references() {
+        let (part1, part2) = (find(2021, 1).unwrap().solve)("199\n200\n208\n210\n200\n207");
+        assert_eq!((part1, part2), (Answer::Integer(4), Answer::Integer(1)));
+        assert!(find(2021, 25).is_none());
+    }
+
+    #[test]
+    fn test_against_reference() {
+        let mut rng = Rng::new(SEED);
+        let mut failures = vec![];
+        for reference in references() {
+            let day = registry::find(reference.year, reference.day).unwrap();
+            let generator = generate::find(reference.year, reference.day).unwrap();
+            let mut smallest: Option<String> = None;
+            for _ in 0..CASES {
+                let size = rng.range(1, MAX_SIZE) as usize;
+                let input = (generator.generate)(&mut rng, size);
+                if smallest.as_ref().is_some_and(|s| s.len() <= input.len()) {
+                    continue;
+                }
+                if disagreement(&day, &reference, &input).is_some() {
+                    smallest = Some(input);
+                }
+            }
+            if let Some(input) = smallest {
+                let input = shrink(&day, &reference, &input);
+                let reason = disagreement(&day, &reference, &input).unwrap();
+                failures.push(format!(
+                    "{} day {}: {} on input\n{}",
+                    day.year, day.day, reason, input
+                ));
+            }
+        }
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    // Too long to print or shrink: reports the seed to regenerate it with.
+    #[test]
+    fn test_large_against_reference() {
+        let mut failures = vec![];
+        for reference in references() {
+            let day = registry::find(reference.year, reference.day).unwrap();
+            let generator = generate::find(reference.year, reference.day).unwrap();
+            let size = generator.default_size * LARGE_FACTOR;
+            for seed in 0..LARGE_CASES {
+                let input = (generator.generate)(&mut Rng::new(seed), size);
+                if let Some(reason) = disagreement(&day, &reference, &input) {
+                    failures.push(format!(
+                        "{} day {}: {} on generate {} --size {} --seed {}",
+                        day.year, day.day, reason, day.day, size, seed
+                    ));
+                }
+            }
+        }
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+}
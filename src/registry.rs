@@ -0,0 +1,144 @@
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    d00_aoc::Solution, y2021::d01_sonar_deep::SonarDeep, y2021::d02_dive::Dive,
+    y2021::d03_binary_diagnostic::BinaryDiagnostic, y2021::d04_giant_squid::GiantSquid,
+    y2021::d05_hydrothermal_veture::HydroThermalVenture,
+};
+
+pub type Parser = fn(&str) -> Result<Box<dyn Solution>, Box<dyn Error>>;
+
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    pub name: &'static str,
+    pub title: &'static str,
+    pub parse: Parser,
+}
+
+// Every season keeps its inputs and answers.txt in a directory of its own.
+pub fn year_dir(inputs_dir: &Path, year: u16) -> PathBuf {
+    inputs_dir.join(year.to_string())
+}
+
+impl Day {
+    pub fn input_path(&self, inputs_dir: &Path) -> PathBuf {
+        year_dir(inputs_dir, self.year).join(format!("d{:02}_input.txt", self.day))
+    }
+
+    // The puzzle's worked example; expected answers go in
+    // src/test_inputs/<year>/answers.txt.
+    pub fn example_path(&self) -> PathBuf {
+        year_dir(Path::new("src/test_inputs"), self.year)
+            .join(format!("d{:02}_test_input.txt", self.day))
+    }
+}
+
+fn parse_solution<S: Solution + 'static>(input: &str) -> Result<Box<dyn Solution>, Box<dyn Error>> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+pub fn days() -> Vec<Day> {
+    vec![
+        Day {
+            year: 2021,
+            day: 1,
+            name: "sonar_deep",
+            title: "Sonar Deep",
+            parse: parse_solution::<SonarDeep>,
+        },
+        Day {
+            year: 2021,
+            day: 2,
+            name: "dive",
+            title: "Dive",
+            parse: parse_solution::<Dive>,
+        },
+        Day {
+            year: 2021,
+            day: 3,
+            name: "binary_diagnostic",
+            title: "Binary Diagnostic",
+            parse: parse_solution::<BinaryDiagnostic>,
+        },
+        Day {
+            year: 2021,
+            day: 4,
+            name: "giant_squid",
+            title: "Giant Squid",
+            parse: parse_solution::<GiantSquid>,
+        },
+        Day {
+            year: 2021,
+            day: 5,
+            name: "hydrothermal_venture",
+            title: "Hydrothermal Venture",
+            parse: parse_solution::<HydroThermalVenture>,
+        },
+    ]
+}
+
+pub fn find(year: u16, day: u8) -> Option<Day> {
+    days().into_iter().find(|d| d.year == year && d.day == day)
+}
+
+pub fn years() -> Vec<u16> {
+    let mut years: Vec<u16> = days().iter().map(|d| d.year).collect();
+    years.sort_unstable();
+    years.dedup();
+    years
+}
+
+pub fn latest_year() -> u16 {
+    years().last().copied().unwrap_or(2021)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::registry::{days, find, latest_year, years};
+
+    #[test]
+    fn test_days_are_unique() {
+        let days = days();
+        for (i, a) in days.iter().enumerate() {
+            for b in &days[i + 1..] {
+                assert!(a.year != b.year || a.day != b.day);
+            }
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        let day = find(2021, 4).unwrap();
+        assert_eq!(day.name, "giant_squid");
+        assert_eq!(
+            day.input_path(Path::new("./inputs")),
+            PathBuf::from("./inputs/2021/d04_input.txt")
+        );
+        assert_eq!(
+            day.example_path(),
+            PathBuf::from("src/test_inputs/2021/d04_test_input.txt")
+        );
+        assert!(find(2021, 25).is_none());
+        assert!(find(2020, 1).is_none());
+    }
+
+    #[test]
+    fn test_parse() {
+        let day = find(2021, 1).unwrap();
+        let solution = (day.parse)("1\n2\n3\n2").unwrap();
+        assert_eq!(solution.part1().to_string(), "2");
+    }
+
+    #[test]
+    fn test_years() {
+        assert_eq!(years()[0], 2021);
+        assert_eq!(latest_year(), *years().last().unwrap());
+    }
+}
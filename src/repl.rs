@@ -0,0 +1,387 @@
+use std::{
+    env,
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    cli,
+    d00_aoc::{query_arg, Solution},
+    journey::load_input,
+    registry::{self, Day},
+    utils::{Puzzle, Status},
+};
+
+const HELP: &str = "Commands:
+  year <YYYY>           switch season (default the latest one)
+  load <DAY> [PATH]     parse a day's input from PATH (default
+                        <inputs-dir>/<YYYY>/dNN_input.txt, where the
+                        inputs dir is $AOC_INPUTS_DIR or ./inputs)
+  paste <DAY>           parse the lines typed up to one holding just 'end'
+  part1, part2          solve a part of the loaded input
+  history [N]           list this session's results, or show result N
+  help                  print this message and the loaded day's queries
+  quit                  leave";
+
+struct Loaded {
+    day: Day,
+    solution: Box<dyn Solution>,
+}
+
+struct Entry {
+    command: String,
+    result: String,
+}
+
+// One REPL session: the loaded day and every result so far. Lines go in
+// through `handle`, which returns what to print.
+pub struct Session {
+    registry: Vec<Day>,
+    inputs_dir: PathBuf,
+    year: u16,
+    loaded: Option<Loaded>,
+    paste: Option<(Day, String)>,
+    history: Vec<Entry>,
+    done: bool,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        let inputs_dir = cli::inputs_dir(&None, env::var_os("AOC_INPUTS_DIR"));
+        Session::new(registry::days(), registry::latest_year(), inputs_dir)
+    }
+}
+
+impl Session {
+    pub fn new(registry: Vec<Day>, year: u16, inputs_dir: PathBuf) -> Session {
+        Session {
+            registry,
+            inputs_dir,
+            year,
+            loaded: None,
+            paste: None,
+            history: vec![],
+            done: false,
+        }
+    }
+
+    pub fn prompt(&self) -> String {
+        match (&self.paste, &self.loaded) {
+            (Some(_), _) => "... ".to_string(),
+            (None, Some(loaded)) => format!("aoc {}/{:02}> ", loaded.day.year, loaded.day.day),
+            (None, None) => format!("aoc {}> ", self.year),
+        }
+    }
+
+    pub fn done(&self) -> bool {
+        self.done
+    }
+
+    pub fn handle(&mut self, line: &str) -> Result<String, Box<dyn Error>> {
+        if let Some((_, input)) = &mut self.paste {
+            if line.trim() != "end" {
+                input.push_str(line);
+                input.push('\n');
+                return Ok(String::new());
+            }
+            let (day, input) = self.paste.take().unwrap();
+            return self.load(day, &input, "the pasted input");
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = match words.split_first() {
+            Some((command, args)) => (*command, args),
+            None => return Ok(String::new()),
+        };
+        match command {
+            "help" => Ok(self.help()),
+            "quit" | "exit" => {
+                self.done = true;
+                Ok(String::new())
+            }
+            "year" => {
+                let year = query_arg(args, 0, "YYYY")?;
+                if !self.registry.iter().any(|d| d.year == year) {
+                    return Err(format!("no day of {} is implemented", year).into());
+                }
+                self.year = year;
+                Ok(format!("Season {}", year))
+            }
+            "load" => {
+                let day = self.day(args)?;
+                let path = match args.get(1) {
+                    Some(&"-") => return Err("use paste to type an input in".into()),
+                    Some(path) => Path::new(path).to_path_buf(),
+                    None => day.input_path(&self.inputs_dir),
+                };
+                let input = load_input(&path)?;
+                self.load(day, &input, &path.display().to_string())
+            }
+            "paste" => {
+                let day = self.day(args)?;
+                self.paste = Some((day, String::new()));
+                Ok("Paste the input, then a line with just 'end'".to_string())
+            }
+            "history" => self.history(args),
+            _ => self.solve(command, args),
+        }
+    }
+
+    fn help(&self) -> String {
+        let mut out = HELP.to_string();
+        if let Some(loaded) = &self.loaded {
+            out += &format!("\n\nQueries for day {}:", loaded.day.day);
+            let queries = loaded.solution.queries();
+            if queries.is_empty() {
+                out += "\n  none";
+            }
+            for (usage, description) in queries {
+                out += &format!("\n  {:<21} {}", usage, description);
+            }
+        }
+        out
+    }
+
+    fn day(&self, args: &[&str]) -> Result<Day, Box<dyn Error>> {
+        let day: u8 = query_arg(args, 0, "DAY")?;
+        self.registry
+            .iter()
+            .find(|d| d.year == self.year && d.day == day)
+            .copied()
+            .ok_or_else(|| format!("day {} of {} is not implemented", day, self.year).into())
+    }
+
+    // Parsed like `solve` runs, so a parser that panics only costs the load.
+    fn load(&mut self, day: Day, input: &str, source: &str) -> Result<String, Box<dyn Error>> {
+        let puzzle = Puzzle::run(day.name, Box::new(|| (day.parse)(input)));
+        let elapsed_time = *puzzle.elapsed_time();
+        let solution = match puzzle.into_status() {
+            Status::Done(solution) => solution?,
+            Status::Panicked(msg) => return Err(format!("panicked: {}", msg).into()),
+            Status::TimedOut(_) => unreachable!(),
+        };
+        self.loaded = Some(Loaded { day, solution });
+        Ok(format!(
+            "Loaded {} day {} :: {} from {} in {} us",
+            day.year,
+            day.day,
+            day.title,
+            source,
+            elapsed_time.as_micros()
+        ))
+    }
+
+    // Parts and the day's queries, run so that a panic only costs the command.
+    fn solve(&mut self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+        let loaded = self
+            .loaded
+            .as_ref()
+            .ok_or("nothing loaded yet, use load or paste first")?;
+        let solution = loaded.solution.as_ref();
+        let puzzle = Puzzle::run(
+            loaded.day.name,
+            Box::new(|| match command {
+                "part1" => Ok(solution.part1().to_string()),
+                "part2" => Ok(solution.part2().to_string()),
+                _ => match solution.query(command, args) {
+                    Some(result) => result.map_err(|e| e.to_string()),
+                    None => Err(format!("unknown command '{}', try help", command)),
+                },
+            }),
+        );
+        let result = match puzzle.status() {
+            Status::Done(Ok(result)) => result.clone(),
+            Status::Done(Err(err)) => return Err(err.clone().into()),
+            Status::Panicked(msg) => return Err(format!("panicked: {}", msg).into()),
+            Status::TimedOut(_) => unreachable!(),
+        };
+        self.history.push(Entry {
+            command: format!(
+                "{}/{:02} {}",
+                loaded.day.year,
+                loaded.day.day,
+                [&[command], args].concat().join(" ")
+            ),
+            result: result.clone(),
+        });
+        let mut out = format!("[{}] ", self.history.len());
+        out += match result.contains('\n') {
+            true => "\n",
+            false => "",
+        };
+        out += &result;
+        if command.starts_with("part") {
+            out += &format!("  ({} us)", puzzle.elapsed_time().as_micros());
+        }
+        Ok(out)
+    }
+
+    fn history(&self, args: &[&str]) -> Result<String, Box<dyn Error>> {
+        if !args.is_empty() {
+            let n: usize = query_arg(args, 0, "N")?;
+            let entry = n
+                .checked_sub(1)
+                .and_then(|i| self.history.get(i))
+                .ok_or_else(|| format!("there is no result {}", n))?;
+            return Ok(format!("[{}] {}\n{}", n, entry.command, entry.result));
+        }
+        if self.history.is_empty() {
+            return Ok("No results yet".to_string());
+        }
+        let lines: Vec<String> = self
+            .history
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let mut result = e.result.lines();
+                let first = result.next().unwrap_or("");
+                let more = if result.next().is_some() { " ..." } else { "" };
+                format!("[{}] {} = {}{}", i + 1, e.command, first, more)
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, error::Error, fs, path::PathBuf, process};
+
+    use crate::{d00_aoc::Solution, registry, repl::Session};
+
+    fn run(session: &mut Session, line: &str) -> String {
+        session
+            .handle(line)
+            .unwrap_or_else(|err| panic!("{}: {}", line, err))
+    }
+
+    // Pinned to 2021 and the repo's inputs, whatever seasons and environment
+    // the runner has.
+    fn session() -> Session {
+        Session::new(registry::days(), 2021, PathBuf::from("inputs"))
+    }
+
+    fn fails(session: &mut Session, line: &str) -> String {
+        match session.handle(line) {
+            Ok(out) => panic!("{} should have failed, printed {}", line, out),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_load_and_solve() {
+        let mut session = session();
+        assert_eq!(session.prompt(), "aoc 2021> ");
+        assert_eq!(
+            fails(&mut session, "part1"),
+            "nothing loaded yet, use load or paste first"
+        );
+        let out = run(
+            &mut session,
+            "load 4 src/test_inputs/2021/d04_test_input.txt",
+        );
+        assert!(out.starts_with("Loaded 2021 day 4 :: Giant Squid from src/test_inputs"));
+        assert_eq!(session.prompt(), "aoc 2021/04> ");
+        assert!(run(&mut session, "part1").starts_with("[1] 4512  ("));
+        assert!(run(&mut session, "part2").starts_with("[2] 1924  ("));
+
+        let out = run(&mut session, "state 12");
+        assert!(out.starts_with("[3] \n12 drawn, the last one 24\n"));
+        assert!(out.contains("board 2 (won)\n*14 *21 *17 *24  *4\n"));
+        assert!(run(&mut session, "help").contains("state <N>"));
+        assert_eq!(fails(&mut session, "state 28"), "there are 27 draws");
+        assert_eq!(
+            fails(&mut session, "bingo"),
+            "unknown command 'bingo', try help"
+        );
+
+        let history = run(&mut session, "history");
+        assert_eq!(
+            history,
+            "[1] 2021/04 part1 = 4512\n[2] 2021/04 part2 = 1924\n[3] 2021/04 state 12 = 12 drawn, the last one 24 ..."
+        );
+        assert!(run(&mut session, "history 3").starts_with("[3] 2021/04 state 12\n12 drawn"));
+        assert_eq!(fails(&mut session, "history 4"), "there is no result 4");
+    }
+
+    #[test]
+    fn test_paste() {
+        let mut session = session();
+        assert!(run(&mut session, "paste 1").starts_with("Paste the input"));
+        assert_eq!(session.prompt(), "... ");
+        for depth in ["199", "200", "208", "210", "200", "207"] {
+            assert_eq!(run(&mut session, depth), "");
+        }
+        assert!(run(&mut session, "end").contains("from the pasted input"));
+        assert!(run(&mut session, "part1").starts_with("[1] 4  ("));
+        assert_eq!(run(&mut session, "depth 3"), "[2] 208");
+
+        run(&mut session, "paste 1");
+        run(&mut session, "x");
+        assert_eq!(
+            fails(&mut session, "end"),
+            "day 1 input, line 1, column 1: expected an integer, found 'x'"
+        );
+        assert_eq!(session.prompt(), "aoc 2021/01> ");
+    }
+
+    #[test]
+    fn test_commands() {
+        let mut session = session();
+        assert_eq!(run(&mut session, "  "), "");
+        assert_eq!(run(&mut session, "history"), "No results yet");
+        assert_eq!(
+            fails(&mut session, "load 26"),
+            "day 26 of 2021 is not implemented"
+        );
+        assert_eq!(fails(&mut session, "load x"), "invalid value 'x' for <DAY>");
+        assert_eq!(fails(&mut session, "paste"), "missing argument <DAY>");
+        assert_eq!(
+            fails(&mut session, "year 1999"),
+            "no day of 1999 is implemented"
+        );
+        assert!(
+            fails(&mut session, "load 1 missing.txt").starts_with("cannot read input missing.txt")
+        );
+        assert!(!session.done());
+        run(&mut session, "quit");
+        assert!(session.done());
+    }
+
+    fn panicking_parse(input: &str) -> Result<Box<dyn Solution>, Box<dyn Error>> {
+        panic!("cannot parse {:?}", input)
+    }
+
+    #[test]
+    fn test_load_panics() {
+        let mut days = registry::days();
+        days[0].parse = panicking_parse;
+        let mut session = Session::new(days, 2021, PathBuf::from("inputs"));
+        run(&mut session, "paste 1");
+        run(&mut session, "1");
+        assert_eq!(
+            fails(&mut session, "end"),
+            "panicked: cannot parse \"1\\n\""
+        );
+        assert_eq!(session.prompt(), "aoc 2021> ");
+        assert!(run(
+            &mut session,
+            "load 4 src/test_inputs/2021/d04_test_input.txt"
+        )
+        .starts_with("Loaded 2021 day 4"));
+    }
+
+    #[test]
+    fn test_inputs_dir() {
+        let dir = env::temp_dir().join(format!("aoc-repl-{}", process::id()));
+        fs::create_dir_all(dir.join("2021")).unwrap();
+        fs::write(dir.join("2021/d01_input.txt"), "199\n200\n208\n").unwrap();
+        let mut session = Session::new(registry::days(), 2021, dir.clone());
+        let out = run(&mut session, "load 1");
+        assert!(out.contains(&format!(
+            "from {}",
+            dir.join("2021/d01_input.txt").display()
+        )));
+        assert!(run(&mut session, "part1").starts_with("[1] 2  ("));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
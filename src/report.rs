@@ -0,0 +1,416 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{
+    answers::Verdict,
+    d00_aoc::Answer,
+    journey::DayReport,
+    memory::{AllocStats, Bytes},
+    registry::Day,
+    utils::{Puzzle, Status},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Solved(DayReport),
+    Error(String),
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Solved(_) => write!(f, "solved"),
+            Outcome::Error(err) => write!(f, "error: {}", err),
+            Outcome::Panicked(msg) => write!(f, "panicked: {}", msg),
+            Outcome::TimedOut(t) => write!(f, "timed out after {} ms", t.as_millis()),
+        }
+    }
+}
+
+impl Outcome {
+    fn message(&self) -> String {
+        match self {
+            Outcome::Error(msg) | Outcome::Panicked(msg) => msg.clone(),
+            outcome => outcome.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RunRecord {
+    pub year: u16,
+    pub day: u8,
+    pub name: String,
+    pub title: String,
+    pub elapsed_time: Duration,
+    // Only measured with the `alloc-stats` feature.
+    pub alloc_stats: Option<AllocStats>,
+    pub outcome: Outcome,
+}
+
+impl RunRecord {
+    pub fn new(day: &Day, puzzle: &Puzzle<Result<DayReport, String>>) -> RunRecord {
+        RunRecord {
+            year: day.year,
+            day: day.day,
+            name: puzzle.name().to_string(),
+            title: day.title.to_string(),
+            elapsed_time: *puzzle.elapsed_time(),
+            alloc_stats: puzzle.alloc_stats().copied(),
+            outcome: match puzzle.status() {
+                Status::Done(Ok(report)) => Outcome::Solved(report.clone()),
+                Status::Done(Err(err)) => Outcome::Error(err.clone()),
+                Status::Panicked(msg) => Outcome::Panicked(msg.clone()),
+                Status::TimedOut(t) => Outcome::TimedOut(*t),
+            },
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        match &self.outcome {
+            Outcome::Solved(report) => report
+                .parts
+                .iter()
+                .any(|p| matches!(p.verdict, Verdict::Fail(_))),
+            _ => true,
+        }
+    }
+
+    fn status(&self) -> &'static str {
+        match &self.outcome {
+            Outcome::Solved(_) if self.failed() => "fail",
+            Outcome::Solved(_) => "ok",
+            Outcome::Error(_) => "error",
+            Outcome::Panicked(_) => "panic",
+            Outcome::TimedOut(_) => "timeout",
+        }
+    }
+}
+
+pub fn write(w: &mut dyn Write, format: Format, records: &[RunRecord]) -> io::Result<()> {
+    match format {
+        Format::Table => write_table(w, records),
+        Format::Json => write_json(w, records),
+        Format::Csv => write_csv(w, records),
+    }
+}
+
+pub fn write_day(w: &mut dyn Write, record: &RunRecord) -> io::Result<()> {
+    writeln!(w, "Day {} of Advent :: {}", record.day, record.title)?;
+    match &record.outcome {
+        Outcome::Solved(report) => {
+            for p in &report.parts {
+                writeln!(w, "  Part {} :: {} [{}]", p.part, p.answer, p.verdict)?;
+            }
+        }
+        outcome => writeln!(w, "  {}", outcome)?,
+    }
+    Ok(())
+}
+
+pub fn write_table(w: &mut dyn Write, records: &[RunRecord]) -> io::Result<()> {
+    writeln!(w)?;
+    for (i, record) in records.iter().enumerate() {
+        write!(
+            w,
+            "Puzzle {0} [{1:^30}] elapsed {2:>7} us",
+            i,
+            record.name,
+            record.elapsed_time.as_micros()
+        )?;
+        if let Some(stats) = &record.alloc_stats {
+            write!(
+                w,
+                "  alloc {:>10} peak {:>10} in {} allocations",
+                Bytes(stats.bytes).to_string(),
+                Bytes(stats.peak).to_string(),
+                stats.count
+            )?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+fn micros(d: Duration) -> String {
+    format!("{:.3}", d.as_secs_f64() * 1e6)
+}
+
+struct Json<'a>(&'a str);
+
+impl fmt::Display for Json<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => write!(f, "\\\"")?,
+                '\\' => write!(f, "\\\\")?,
+                '\n' => write!(f, "\\n")?,
+                '\r' => write!(f, "\\r")?,
+                '\t' => write!(f, "\\t")?,
+                c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Integer(n) => n.to_string(),
+        Answer::Text(s) => Json(s).to_string(),
+    }
+}
+
+fn verdict_parts(verdict: &Verdict) -> (&'static str, Option<&str>) {
+    match verdict {
+        Verdict::Pass => ("pass", None),
+        Verdict::Fail(expected) => ("fail", Some(expected)),
+        Verdict::Unknown => ("unknown", None),
+    }
+}
+
+pub fn write_json(w: &mut dyn Write, records: &[RunRecord]) -> io::Result<()> {
+    writeln!(w, "[")?;
+    for (i, record) in records.iter().enumerate() {
+        write!(
+            w,
+            "  {{\"year\": {}, \"day\": {}, \"name\": {}, \"status\": \"{}\", \"elapsed_us\": {}",
+            record.year,
+            record.day,
+            Json(&record.name),
+            record.status(),
+            micros(record.elapsed_time)
+        )?;
+        if let Some(stats) = &record.alloc_stats {
+            write!(
+                w,
+                ", \"alloc_bytes\": {}, \"peak_bytes\": {}, \"allocs\": {}",
+                stats.bytes, stats.peak, stats.count
+            )?;
+        }
+        match &record.outcome {
+            Outcome::Solved(report) => {
+                write!(
+                    w,
+                    ", \"parse_us\": {}, \"parts\": [",
+                    micros(report.parse_time)
+                )?;
+                for (j, p) in report.parts.iter().enumerate() {
+                    let (verdict, expected) = verdict_parts(&p.verdict);
+                    write!(
+                        w,
+                        "{}{{\"part\": {}, \"answer\": {}, \"solve_us\": {}, \"verdict\": \"{}\"",
+                        if j > 0 { ", " } else { "" },
+                        p.part,
+                        json_answer(&p.answer),
+                        micros(p.elapsed_time),
+                        verdict
+                    )?;
+                    if let Some(expected) = expected {
+                        write!(w, ", \"expected\": {}", Json(expected))?;
+                    }
+                    write!(w, "}}")?;
+                }
+                write!(w, "]}}")?;
+            }
+            outcome => write!(w, ", \"error\": {}}}", Json(&outcome.message()))?,
+        }
+        writeln!(w, "{}", if i + 1 < records.len() { "," } else { "" })?;
+    }
+    writeln!(w, "]")
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+pub fn write_csv(w: &mut dyn Write, records: &[RunRecord]) -> io::Result<()> {
+    writeln!(
+        w,
+        "year,day,name,status,part,answer,verdict,expected,parse_us,solve_us,elapsed_us,alloc_bytes,peak_bytes,allocs,error"
+    )?;
+    for record in records {
+        let prefix = format!(
+            "{},{},{},{}",
+            record.year,
+            record.day,
+            csv_field(&record.name),
+            record.status()
+        );
+        let alloc = match &record.alloc_stats {
+            Some(stats) => format!("{},{},{}", stats.bytes, stats.peak, stats.count),
+            None => ",,".to_string(),
+        };
+        match &record.outcome {
+            Outcome::Solved(report) => {
+                for p in &report.parts {
+                    let (verdict, expected) = verdict_parts(&p.verdict);
+                    writeln!(
+                        w,
+                        "{},{},{},{},{},{},{},{},{},",
+                        prefix,
+                        p.part,
+                        csv_field(&p.answer.to_string()),
+                        verdict,
+                        csv_field(expected.unwrap_or("")),
+                        micros(report.parse_time),
+                        micros(p.elapsed_time),
+                        micros(record.elapsed_time),
+                        alloc
+                    )?;
+                }
+            }
+            outcome => writeln!(
+                w,
+                "{},,,,,,,{},{},{}",
+                prefix,
+                micros(record.elapsed_time),
+                alloc,
+                csv_field(&outcome.message())
+            )?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        answers::Verdict,
+        d00_aoc::Answer,
+        journey::{DayReport, PartReport},
+        memory::AllocStats,
+        report::{write_csv, write_json, write_table, Format, Outcome, RunRecord},
+    };
+
+    fn get_records() -> Vec<RunRecord> {
+        vec![
+            RunRecord {
+                year: 2021,
+                day: 1,
+                name: "sonar_deep".to_string(),
+                title: "Sonar Deep".to_string(),
+                elapsed_time: Duration::from_micros(10),
+                alloc_stats: Some(AllocStats {
+                    bytes: 12595,
+                    peak: 4096,
+                    count: 7,
+                }),
+                outcome: Outcome::Solved(DayReport {
+                    parse_time: Duration::from_micros(4),
+                    parts: vec![
+                        PartReport {
+                            part: 1,
+                            answer: Answer::Integer(1393),
+                            elapsed_time: Duration::from_micros(1),
+                            verdict: Verdict::Pass,
+                        },
+                        PartReport {
+                            part: 2,
+                            answer: Answer::from("a,\"b\""),
+                            elapsed_time: Duration::from_micros(2),
+                            verdict: Verdict::Fail("c".to_string()),
+                        },
+                    ],
+                }),
+            },
+            RunRecord {
+                year: 2021,
+                day: 2,
+                name: "dive".to_string(),
+                title: "Dive".to_string(),
+                elapsed_time: Duration::from_micros(3),
+                alloc_stats: None,
+                outcome: Outcome::Error("line 1".to_string()),
+            },
+            RunRecord {
+                year: 2021,
+                day: 3,
+                name: "binary_diagnostic".to_string(),
+                title: "Binary Diagnostic".to_string(),
+                elapsed_time: Duration::from_micros(5),
+                alloc_stats: None,
+                outcome: Outcome::Panicked("no way!".to_string()),
+            },
+        ]
+    }
+
+    fn to_string(f: fn(&mut dyn std::io::Write, &[RunRecord]) -> std::io::Result<()>) -> String {
+        let mut out = vec![];
+        f(&mut out, &get_records()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("csv".parse(), Ok(Format::Csv));
+        assert_eq!("table".parse(), Ok(Format::Table));
+        assert_eq!("xml".parse::<Format>(), Err(()));
+    }
+
+    #[test]
+    fn test_write_json() {
+        let expected = r#"[
+  {"year": 2021, "day": 1, "name": "sonar_deep", "status": "fail", "elapsed_us": 10.000, "alloc_bytes": 12595, "peak_bytes": 4096, "allocs": 7, "parse_us": 4.000, "parts": [{"part": 1, "answer": 1393, "solve_us": 1.000, "verdict": "pass"}, {"part": 2, "answer": "a,\"b\"", "solve_us": 2.000, "verdict": "fail", "expected": "c"}]},
+  {"year": 2021, "day": 2, "name": "dive", "status": "error", "elapsed_us": 3.000, "error": "line 1"},
+  {"year": 2021, "day": 3, "name": "binary_diagnostic", "status": "panic", "elapsed_us": 5.000, "error": "no way!"}
+]
+"#;
+        assert_eq!(to_string(write_json), expected);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let expected =
+            "year,day,name,status,part,answer,verdict,expected,parse_us,solve_us,elapsed_us,alloc_bytes,peak_bytes,allocs,error
+2021,1,sonar_deep,fail,1,1393,pass,,4.000,1.000,10.000,12595,4096,7,
+2021,1,sonar_deep,fail,2,\"a,\"\"b\"\"\",fail,c,4.000,2.000,10.000,12595,4096,7,
+2021,2,dive,error,,,,,,,3.000,,,,line 1
+2021,3,binary_diagnostic,panic,,,,,,,5.000,,,,no way!
+";
+        assert_eq!(to_string(write_csv), expected);
+    }
+
+    #[test]
+    fn test_write_table() {
+        let table = to_string(write_table);
+        assert!(table.contains("Puzzle 1 [             dive             ] elapsed       3 us\n"));
+        assert!(table
+            .contains("elapsed      10 us  alloc   12.3 KiB peak    4.0 KiB in 7 allocations\n"));
+    }
+}
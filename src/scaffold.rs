@@ -0,0 +1,357 @@
+use std::{
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::cli::NewDay;
+
+const MODULE_TEMPLATE: &str = r#"use std::{error::Error, io::BufRead};
+
+use crate::{
+    d00_aoc::{for_each_line, Answer, InputReader, ParseError, Solution},
+    parse,
+};
+
+pub struct {Type} {
+    input: Vec<i64>,
+}
+
+impl InputReader<i64> for {Type} {
+    fn string_to_vector(input_str: String) -> Result<Vec<i64>, ParseError> {
+        parse::ints({day}, &input_str)
+    }
+
+    fn from_reader<R: BufRead>(reader: R) -> Result<Self, Box<dyn Error>> {
+        let mut input = vec![];
+        for_each_line(reader, |n, line| {
+            input.push(parse::int_line({day}, n, line)?);
+            Ok(())
+        })?;
+        Ok({Type} { input })
+    }
+}
+
+impl Solution for {Type} {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        Self::from_reader(input.as_bytes())
+    }
+
+    fn part1(&self) -> Answer {
+        todo!("solve part 1 for {} values", self.input.len())
+    }
+
+    fn part2(&self) -> Answer {
+        todo!("solve part 2 for {} values", self.input.len())
+    }
+}
+
+impl {Type} {
+    pub fn new(input: Vec<i64>) -> {Type} {
+        {Type} { input }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{d00_aoc::InputReader, {module}::{Type}};
+
+    #[test]
+    fn test_string_to_vec() {
+        let r = {Type}::string_to_vector("1\n2\n3\n".to_string()).unwrap();
+        assert_eq!(r, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_string_to_vec_malformed() {
+        let err = {Type}::string_to_vector("1\nx\n3".to_string()).unwrap_err();
+        assert_eq!(err.day, {day});
+        assert_eq!(err.line, 2);
+        assert_eq!(err.text, "x");
+    }
+}
+"#;
+
+pub fn module_name(day: u8, name: &str) -> String {
+    format!("d{:02}_{}", day, name)
+}
+
+pub fn year_module(year: u16) -> String {
+    format!("y{}", year)
+}
+
+pub fn type_name(name: &str) -> String {
+    name.split('_')
+        .filter(|w| !w.is_empty())
+        .map(|w| w[..1].to_uppercase() + &w[1..])
+        .collect()
+}
+
+pub fn module_source(new_day: &NewDay) -> String {
+    MODULE_TEMPLATE
+        .replace("{Type}", &type_name(&new_day.name))
+        .replace(
+            "{module}",
+            &format!(
+                "{}::{}",
+                year_module(new_day.year),
+                module_name(new_day.day, &new_day.name)
+            ),
+        )
+        .replace("{day}", &new_day.day.to_string())
+}
+
+// Keeps the `pub mod` lines of lib.rs or a year's mod.rs sorted, as rustfmt
+// would leave them.
+pub fn register_module(mod_rs: &str, module: &str) -> Result<String, Box<dyn Error>> {
+    let line = format!("pub mod {};", module);
+    let mut lines: Vec<&str> = mod_rs.lines().collect();
+    if lines.contains(&line.as_str()) {
+        return Err(format!("module {} is already declared", module).into());
+    }
+    let position = lines
+        .iter()
+        .position(|l| l.starts_with("pub mod ") && *l > line.as_str())
+        .unwrap_or(lines.len());
+    lines.insert(position, &line);
+    Ok(lines.join("\n") + "\n")
+}
+
+pub fn register_in_registry(registry_rs: &str, new_day: &NewDay) -> Result<String, Box<dyn Error>> {
+    let existing = format!(
+        "year: {},\n            day: {},\n",
+        new_day.year, new_day.day
+    );
+    if registry_rs.contains(&existing) {
+        return Err(format!(
+            "day {} of {} is already registered",
+            new_day.day, new_day.year
+        )
+        .into());
+    }
+    let module = module_name(new_day.day, &new_day.name);
+    let type_name = type_name(&new_day.name);
+
+    let imports_start = registry_rs
+        .find("use crate::{")
+        .ok_or("registry.rs has no `use crate::{...}` import")?;
+    let imports_end = imports_start
+        + registry_rs[imports_start..]
+            .find("};")
+            .ok_or("registry.rs has an unterminated import")?;
+    let mut imports: Vec<String> = registry_rs[imports_start + "use crate::{".len()..imports_end]
+        .split(',')
+        .map(|i| i.trim().to_string())
+        .filter(|i| !i.is_empty())
+        .collect();
+    imports.push(format!(
+        "{}::{}::{}",
+        year_module(new_day.year),
+        module,
+        type_name
+    ));
+    imports.sort();
+
+    let days_end = registry_rs
+        .find("    ]\n}")
+        .ok_or("registry.rs has no `days()` list")?;
+    let entry = format!(
+        "        Day {{\n            year: {},\n            day: {},\n            name: \"{}\",\n            title: \"{}\",\n            parse: parse_solution::<{}>,\n        }},\n",
+        new_day.year,
+        new_day.day,
+        new_day.name,
+        new_day.title.replace('\\', "\\\\").replace('"', "\\\""),
+        type_name
+    );
+
+    Ok(format!(
+        "{}{}{}{}{}",
+        &registry_rs[..imports_start],
+        wrap_imports(&imports),
+        &registry_rs[imports_end + "};".len()..days_end],
+        entry,
+        &registry_rs[days_end..]
+    ))
+}
+
+fn wrap_imports(imports: &[String]) -> String {
+    let mut out = String::from("use crate::{\n");
+    let mut line = String::new();
+    for import in imports {
+        if !line.is_empty() && 4 + line.len() + 1 + import.len() + 1 > 100 {
+            out += &format!("    {}\n", line);
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line += import;
+        line.push(',');
+    }
+    out += &format!("    {}\n}};", line);
+    out
+}
+
+// Everything is checked before the first write, so a failed run leaves the
+// tree untouched. Returns the files created or modified.
+pub fn new_day(root: &Path, new_day: &NewDay) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let year = year_module(new_day.year);
+    let module = module_name(new_day.day, &new_day.name);
+    let year_path = root.join("src").join(&year);
+    let module_path = year_path.join(format!("{}.rs", module));
+    if module_path.exists() {
+        return Err(format!("{} already exists", module_path.display()).into());
+    }
+    let lib_path = root.join("src/lib.rs");
+    let mod_path = year_path.join("mod.rs");
+    let registry_path = root.join("src/registry.rs");
+    // The first day of a season also declares the season's module.
+    let lib_rs = if mod_path.exists() {
+        None
+    } else {
+        Some(register_module(&fs::read_to_string(&lib_path)?, &year)?)
+    };
+    let mod_rs = match fs::read_to_string(&mod_path) {
+        Ok(mod_rs) => register_module(&mod_rs, &module)?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => register_module("", &module)?,
+        Err(err) => return Err(Box::new(err)),
+    };
+    let registry_rs = register_in_registry(&fs::read_to_string(&registry_path)?, new_day)?;
+
+    fs::create_dir_all(&year_path)?;
+    fs::write(&module_path, module_source(new_day))?;
+    fs::write(&mod_path, mod_rs)?;
+    let mut touched = vec![module_path, mod_path];
+    if let Some(lib_rs) = lib_rs {
+        fs::write(&lib_path, lib_rs)?;
+        touched.push(lib_path);
+    }
+    fs::write(&registry_path, registry_rs)?;
+    touched.push(registry_path);
+
+    let day = format!("d{:02}", new_day.day);
+    let season = new_day.year.to_string();
+    for placeholder in [
+        root.join("inputs")
+            .join(&season)
+            .join(format!("{}_input.txt", day)),
+        root.join("src/test_inputs")
+            .join(&season)
+            .join(format!("{}_test_input.txt", day)),
+    ] {
+        if !placeholder.exists() {
+            if let Some(dir) = placeholder.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&placeholder, "")?;
+            touched.push(placeholder);
+        }
+    }
+    Ok(touched)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::Path, process};
+
+    use crate::{
+        cli::NewDay,
+        scaffold::{module_source, new_day, register_in_registry, register_module, type_name},
+    };
+
+    fn lanternfish() -> NewDay {
+        NewDay {
+            year: 2021,
+            day: 6,
+            name: "lanternfish".to_string(),
+            title: "Lanternfish".to_string(),
+            help: false,
+        }
+    }
+
+    const REGISTRY_RS: &str = "use crate::{d00_aoc::Solution, y2021::d05_x::X};
+
+pub fn days() -> Vec<Day> {
+    vec![
+        Day {
+            year: 2021,
+            day: 5,
+            name: \"x\",
+            title: \"X\",
+            parse: parse_solution::<X>,
+        },
+    ]
+}
+";
+
+    #[test]
+    fn test_type_name() {
+        assert_eq!(type_name("lanternfish"), "Lanternfish");
+        assert_eq!(type_name("treachery_of_whales"), "TreacheryOfWhales");
+    }
+
+    #[test]
+    fn test_module_source() {
+        let source = module_source(&lanternfish());
+        assert!(source.contains("pub struct Lanternfish {"));
+        assert!(source.contains("d00_aoc::InputReader, y2021::d06_lanternfish::Lanternfish"));
+        assert!(!source.contains("{Type}"));
+    }
+
+    #[test]
+    fn test_register_module() {
+        let lib_rs = "pub mod d00_aoc;\npub mod journey;\npub mod y2021;\n";
+        assert_eq!(
+            register_module(lib_rs, "y2022").unwrap(),
+            "pub mod d00_aoc;\npub mod journey;\npub mod y2021;\npub mod y2022;\n"
+        );
+        assert!(register_module(lib_rs, "y2021").is_err());
+        assert_eq!(register_module("", "d01_x").unwrap(), "pub mod d01_x;\n");
+    }
+
+    #[test]
+    fn test_register_in_registry() {
+        let updated = register_in_registry(REGISTRY_RS, &lanternfish()).unwrap();
+        assert!(updated.starts_with(
+            "use crate::{\n    d00_aoc::Solution, y2021::d05_x::X, y2021::d06_lanternfish::Lanternfish,\n};\n"
+        ));
+        assert!(updated.contains(
+            "            day: 6,\n            name: \"lanternfish\",\n            title: \"Lanternfish\",\n            parse: parse_solution::<Lanternfish>,\n        },\n    ]\n}"
+        ));
+        assert!(register_in_registry(&updated, &lanternfish()).is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = env::temp_dir().join(format!("aoc-scaffold-{}", process::id()));
+        fs::create_dir_all(root.join("src/y2021")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub mod y2021;\n").unwrap();
+        fs::write(root.join("src/y2021/mod.rs"), "pub mod d05_x;\n").unwrap();
+        fs::write(root.join("src/registry.rs"), REGISTRY_RS).unwrap();
+
+        let touched = new_day(&root, &lanternfish()).unwrap();
+        assert_eq!(touched.len(), 5);
+        assert!(root.join("src/y2021/d06_lanternfish.rs").exists());
+        assert!(root.join("inputs/2021/d06_input.txt").exists());
+        assert!(root
+            .join("src/test_inputs/2021/d06_test_input.txt")
+            .exists());
+        let mod_rs = fs::read_to_string(root.join("src/y2021/mod.rs")).unwrap();
+        assert_eq!(mod_rs, "pub mod d05_x;\npub mod d06_lanternfish;\n");
+        assert!(new_day(&root, &lanternfish()).is_err());
+
+        let mut calorie_counting = lanternfish();
+        calorie_counting.year = 2022;
+        calorie_counting.day = 1;
+        calorie_counting.name = "calorie_counting".to_string();
+        let touched = new_day(&root, &calorie_counting).unwrap();
+        assert_eq!(touched.len(), 6);
+        let lib_rs = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert_eq!(lib_rs, "pub mod y2021;\npub mod y2022;\n");
+        let mod_rs = fs::read_to_string(root.join("src/y2022/mod.rs")).unwrap();
+        assert_eq!(mod_rs, "pub mod d01_calorie_counting;\n");
+
+        fs::remove_dir_all(&root).unwrap();
+        assert!(!Path::new(&root).exists());
+    }
+}
@@ -0,0 +1,560 @@
+use std::{
+    cmp::Ordering,
+    error::Error,
+    fmt,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{
+    fetch::{self, Http, BASE_URL, USER_AGENT},
+    records,
+};
+
+// Where the ledger lives unless --ledger says otherwise.
+pub const LEDGER_FILE: &str = ".aoc-ledger";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Feedback {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    // Answered too recently; try again after the duration.
+    Wait(Duration),
+    // The part is already solved, or part 1 is not yet.
+    WrongLevel,
+}
+
+impl Feedback {
+    // Only these were judged by the server, so only these go in the ledger.
+    pub fn is_verdict(&self) -> bool {
+        !matches!(self, Feedback::Wait(_) | Feedback::WrongLevel)
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Feedback::Correct => write!(f, "right"),
+            Feedback::TooHigh => write!(f, "high"),
+            Feedback::TooLow => write!(f, "low"),
+            Feedback::Wrong => write!(f, "wrong"),
+            Feedback::Wait(left) => write!(f, "wait {}s", left.as_secs()),
+            Feedback::WrongLevel => write!(f, "level"),
+        }
+    }
+}
+
+impl FromStr for Feedback {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "right" => Ok(Feedback::Correct),
+            "high" => Ok(Feedback::TooHigh),
+            "low" => Ok(Feedback::TooLow),
+            "wrong" => Ok(Feedback::Wrong),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub time: u64,
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub feedback: Feedback,
+    pub answer: String,
+}
+
+// Why an answer was not sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    Solved(String),
+    Rejected(String, Feedback),
+    NotBelow(String, String),
+    NotAbove(String, String),
+    Malformed(String),
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::Solved(answer) => write!(f, "already solved, the answer is {}", answer),
+            Refusal::Rejected(answer, Feedback::TooHigh) => {
+                write!(f, "{} was already rejected as too high", answer)
+            }
+            Refusal::Rejected(answer, Feedback::TooLow) => {
+                write!(f, "{} was already rejected as too low", answer)
+            }
+            Refusal::Rejected(answer, _) => write!(f, "{} was already rejected", answer),
+            Refusal::NotBelow(answer, bound) => {
+                write!(f, "{} is too high, {} already was", answer, bound)
+            }
+            Refusal::NotAbove(answer, bound) => {
+                write!(f, "{} is too low, {} already was", answer, bound)
+            }
+            Refusal::Malformed(answer) => {
+                write!(
+                    f,
+                    "'{}' cannot be an answer, it is empty or holds spaces",
+                    answer
+                )
+            }
+        }
+    }
+}
+
+impl Error for Refusal {}
+
+// Every answer the server judged, by year, day and part, kept as
+// "time year day part feedback answer" lines.
+#[derive(Debug, Default, Clone)]
+pub struct Ledger {
+    submissions: Vec<Submission>,
+}
+
+impl Ledger {
+    pub fn parse(input_str: &str) -> Result<Ledger, Box<dyn Error>> {
+        let submissions = records::parse(input_str, "ledger", |fields| {
+            let submission = match fields {
+                [time, year, day, part, feedback, answer] => {
+                    match (
+                        time.parse(),
+                        year.parse(),
+                        day.parse(),
+                        part.parse(),
+                        feedback.parse(),
+                    ) {
+                        (Ok(time), Ok(year), Ok(day), Ok(part), Ok(feedback)) => Some(Submission {
+                            time,
+                            year,
+                            day,
+                            part,
+                            feedback,
+                            answer: answer.to_string(),
+                        }),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            submission.ok_or_else(|| "expected 'time year day part feedback answer'".to_string())
+        })?;
+        Ok(Ledger { submissions })
+    }
+
+    pub fn from_file_or_empty(path: &Path) -> Result<Ledger, Box<dyn Error>> {
+        Self::parse(&records::read_or_empty(path)?)
+    }
+
+    pub fn append(path: &Path, submission: &Submission) -> io::Result<()> {
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if is_new {
+            writeln!(file, "# time year day part feedback answer")?;
+        }
+        let s = submission;
+        writeln!(
+            file,
+            "{} {} {:02} {} {} {}",
+            s.time, s.year, s.day, s.part, s.feedback, s.answer
+        )
+    }
+
+    pub fn record(&mut self, submission: Submission) {
+        self.submissions.push(submission);
+    }
+
+    // Oldest first.
+    pub fn submissions(&self, year: u16, day: u8, part: u8) -> Vec<&Submission> {
+        self.submissions
+            .iter()
+            .filter(|s| s.year == year && s.day == day && s.part == part)
+            .collect()
+    }
+
+    // Whether `answer` is worth sending, given what the server said so far.
+    pub fn check(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<(), Refusal> {
+        if answer.is_empty() || answer.contains(char::is_whitespace) {
+            return Err(Refusal::Malformed(answer.to_string()));
+        }
+        let submissions = self.submissions(year, day, part);
+        if let Some(s) = submissions.iter().find(|s| s.feedback == Feedback::Correct) {
+            return Err(Refusal::Solved(s.answer.clone()));
+        }
+        if let Some(s) = submissions.iter().find(|s| s.answer == answer) {
+            return Err(Refusal::Rejected(answer.to_string(), s.feedback.clone()));
+        }
+        let bound = |feedback: Feedback, order: Ordering| {
+            submissions
+                .iter()
+                .filter(|s| s.feedback == feedback)
+                .filter_map(|s| Some((s.answer.parse::<i64>().ok()?, &s.answer)))
+                .reduce(|a, b| if a.0.cmp(&b.0) == order { a } else { b })
+        };
+        if let Ok(value) = answer.parse::<i64>() {
+            if let Some((high, text)) = bound(Feedback::TooHigh, Ordering::Less) {
+                if value >= high {
+                    return Err(Refusal::NotBelow(answer.to_string(), text.clone()));
+                }
+            }
+            if let Some((low, text)) = bound(Feedback::TooLow, Ordering::Greater) {
+                if value <= low {
+                    return Err(Refusal::NotAbove(answer.to_string(), text.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Whatever judges answers: `AocServer` for the site, `FakeServer` for tests.
+pub trait Server {
+    fn submit(
+        &mut self,
+        year: u16,
+        day: u8,
+        part: u8,
+        answer: &str,
+    ) -> Result<Feedback, Box<dyn Error>>;
+}
+
+pub struct AocServer<H: Http> {
+    http: H,
+    session: String,
+    base_url: String,
+}
+
+impl<H: Http> AocServer<H> {
+    pub fn new(http: H, session: &str) -> AocServer<H> {
+        AocServer {
+            http,
+            session: session.to_string(),
+            base_url: BASE_URL.to_string(),
+        }
+    }
+
+    pub fn base_url(mut self, base_url: &str) -> AocServer<H> {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn http(&self) -> &H {
+        &self.http
+    }
+}
+
+impl<H: Http> Server for AocServer<H> {
+    fn submit(
+        &mut self,
+        year: u16,
+        day: u8,
+        part: u8,
+        answer: &str,
+    ) -> Result<Feedback, Box<dyn Error>> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+        let cookie = format!("session={}", self.session);
+        let form = format!("level={}&answer={}", part, url_encode(answer));
+        let headers = [
+            ("Cookie", cookie.as_str()),
+            ("User-Agent", USER_AGENT),
+            ("Content-Type", "application/x-www-form-urlencoded"),
+        ];
+        let response = self.http.post(&url, &headers, &form)?;
+        match response.status {
+            200 => parse_feedback(&response.body),
+            status => Err(fetch::status_error(&url, status)),
+        }
+    }
+}
+
+fn url_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// The verdict is only in the prose of the page the site answers with.
+pub fn parse_feedback(page: &str) -> Result<Feedback, Box<dyn Error>> {
+    if page.contains("That's the right answer") {
+        Ok(Feedback::Correct)
+    } else if page.contains("You gave an answer too recently") {
+        Ok(Feedback::Wait(wait_left(page).unwrap_or_default()))
+    } else if page.contains("your answer is too high") {
+        Ok(Feedback::TooHigh)
+    } else if page.contains("your answer is too low") {
+        Ok(Feedback::TooLow)
+    } else if page.contains("That's not the right answer") {
+        Ok(Feedback::Wrong)
+    } else if page.contains("You don't seem to be solving the right level") {
+        Ok(Feedback::WrongLevel)
+    } else {
+        Err("the site's reply holds no verdict".into())
+    }
+}
+
+// "You have 4m 3s left to wait", or "You have 43s left to wait".
+fn wait_left(page: &str) -> Option<Duration> {
+    let start = page.find("You have ")? + "You have ".len();
+    let end = start + page[start..].find(" left to wait")?;
+    let mut seconds = 0;
+    for token in page[start..end].split_whitespace() {
+        seconds += match token.split_at(token.len().checked_sub(1)?) {
+            (n, "m") => n.parse::<u64>().ok()? * 60,
+            (n, "s") => n.parse::<u64>().ok()?,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+// Checks `answer` against the ledger at `ledger_path`, sends it if worth
+// sending, and records the verdict. Refusals come back as a `Refusal` error.
+pub fn submit<S: Server>(
+    server: &mut S,
+    ledger_path: &Path,
+    (year, day, part): (u16, u8, u8),
+    answer: &str,
+    time: u64,
+) -> Result<Feedback, Box<dyn Error>> {
+    let ledger = Ledger::from_file_or_empty(ledger_path)?;
+    ledger.check(year, day, part, answer)?;
+    let feedback = server.submit(year, day, part, answer)?;
+    if feedback.is_verdict() {
+        let submission = Submission {
+            time,
+            year,
+            day,
+            part,
+            feedback: feedback.clone(),
+            answer: answer.to_string(),
+        };
+        Ledger::append(ledger_path, &submission)?;
+    }
+    Ok(feedback)
+}
+
+#[cfg(test)]
+pub use fake::FakeServer;
+
+// Test doubles, kept out of the library's API.
+#[cfg(test)]
+mod fake {
+    use std::{collections::HashMap, error::Error};
+
+    use crate::submit::{Feedback, Server};
+
+    // Knows the right answers and judges the way the site does; numeric answers
+    // that miss are too high or too low.
+    #[derive(Default)]
+    pub struct FakeServer {
+        answers: HashMap<(u16, u8, u8), String>,
+        submitted: Vec<(u16, u8, u8, String)>,
+    }
+
+    impl FakeServer {
+        pub fn answer(&mut self, year: u16, day: u8, part: u8, answer: &str) {
+            self.answers.insert((year, day, part), answer.to_string());
+        }
+
+        // Oldest first.
+        pub fn submitted(&self) -> &[(u16, u8, u8, String)] {
+            &self.submitted
+        }
+    }
+
+    impl Server for FakeServer {
+        fn submit(
+            &mut self,
+            year: u16,
+            day: u8,
+            part: u8,
+            answer: &str,
+        ) -> Result<Feedback, Box<dyn Error>> {
+            self.submitted.push((year, day, part, answer.to_string()));
+            let expected = self
+                .answers
+                .get(&(year, day, part))
+                .ok_or_else(|| format!("day {} of {} is not unlocked yet", day, year))?;
+            let feedback = match (answer.parse::<i64>(), expected.parse::<i64>()) {
+                _ if answer == expected => Feedback::Correct,
+                (Ok(a), Ok(e)) if a > e => Feedback::TooHigh,
+                (Ok(_), Ok(_)) => Feedback::TooLow,
+                _ => Feedback::Wrong,
+            };
+            Ok(feedback)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf, process, time::Duration};
+
+    use crate::{
+        fetch::FakeHttp,
+        submit::{self, AocServer, FakeServer, Feedback, Ledger, Refusal, Server, Submission},
+    };
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("aoc-ledger-{}-{}", name, process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn submission(part: u8, feedback: Feedback, answer: &str) -> Submission {
+        Submission {
+            time: 1638600000,
+            year: 2021,
+            day: 4,
+            part,
+            feedback,
+            answer: answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let mut ledger = Ledger::default();
+        assert_eq!(ledger.check(2021, 4, 1, "51776"), Ok(()));
+        ledger.record(submission(1, Feedback::TooHigh, "60000"));
+        ledger.record(submission(1, Feedback::TooHigh, "52000"));
+        ledger.record(submission(1, Feedback::TooLow, "40000"));
+        ledger.record(submission(1, Feedback::Wrong, "abc"));
+
+        assert_eq!(
+            ledger.check(2021, 4, 1, "52000").unwrap_err().to_string(),
+            "52000 was already rejected as too high"
+        );
+        assert_eq!(
+            ledger.check(2021, 4, 1, "55000"),
+            Err(Refusal::NotBelow("55000".to_string(), "52000".to_string()))
+        );
+        assert_eq!(
+            ledger.check(2021, 4, 1, "39999").unwrap_err().to_string(),
+            "39999 is too low, 40000 already was"
+        );
+        assert!(ledger.check(2021, 4, 1, "abc").is_err());
+        assert_eq!(ledger.check(2021, 4, 1, "51776"), Ok(()));
+        assert_eq!(ledger.check(2021, 4, 1, "xyz"), Ok(()));
+        assert_eq!(ledger.check(2021, 4, 2, "60000"), Ok(()));
+        assert!(ledger.check(2021, 4, 2, "1 2").is_err());
+
+        ledger.record(submission(1, Feedback::Correct, "51776"));
+        assert_eq!(
+            ledger.check(2021, 4, 1, "51000").unwrap_err().to_string(),
+            "already solved, the answer is 51776"
+        );
+    }
+
+    #[test]
+    fn test_ledger_file() {
+        let path = temp_path("file");
+        Ledger::append(&path, &submission(1, Feedback::TooLow, "40000")).unwrap();
+        Ledger::append(&path, &submission(2, Feedback::Correct, "16830")).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "# time year day part feedback answer\n1638600000 2021 04 1 low 40000\n1638600000 2021 04 2 right 16830\n"
+        );
+        let ledger = Ledger::from_file_or_empty(&path).unwrap();
+        assert_eq!(
+            ledger.submissions(2021, 4, 2),
+            vec![&submission(2, Feedback::Correct, "16830")]
+        );
+        fs::remove_file(&path).unwrap();
+
+        assert!(Ledger::parse("1638600000 2021 04 1 maybe 40000").is_err());
+        assert!(Ledger::parse("1638600000 2021 04 1 low").is_err());
+        assert_eq!(
+            Ledger::from_file_or_empty(&path)
+                .unwrap()
+                .submissions(2021, 4, 1),
+            Vec::<&Submission>::new()
+        );
+    }
+
+    #[test]
+    fn test_submit() {
+        let path = temp_path("submit");
+        let mut server = FakeServer::default();
+        server.answer(2021, 4, 1, "51776");
+        let mut send = |answer: &str| submit::submit(&mut server, &path, (2021, 4, 1), answer, 1);
+
+        assert_eq!(send("60000").unwrap(), Feedback::TooHigh);
+        assert_eq!(send("40000").unwrap(), Feedback::TooLow);
+        assert_eq!(
+            send("60001").unwrap_err().to_string(),
+            "60001 is too high, 60000 already was"
+        );
+        assert!(send("40000").is_err());
+        assert_eq!(send("51776").unwrap(), Feedback::Correct);
+        assert!(send("51777").is_err());
+        assert_eq!(server.submitted().len(), 3);
+
+        let ledger = Ledger::from_file_or_empty(&path).unwrap();
+        assert_eq!(ledger.submissions(2021, 4, 1).len(), 3);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_aoc_server() {
+        let url = "https://adventofcode.com/2021/day/4/answer";
+        let mut http = FakeHttp::default();
+        http.respond(
+            url,
+            200,
+            "<article><p>That's not the right answer; your answer is too high.  If you're stuck, ...</p></article>",
+        );
+        let mut server = AocServer::new(http, "abc123");
+        assert_eq!(
+            server.submit(2021, 4, 2, "16 830").unwrap(),
+            Feedback::TooHigh
+        );
+        let request = &server.http().requests()[0];
+        assert_eq!(request.url, url);
+        assert_eq!(request.form.as_deref(), Some("level=2&answer=16%20830"));
+        assert!(request
+            .headers
+            .contains(&("Cookie".to_string(), "session=abc123".to_string())));
+        assert!(server.submit(2021, 5, 1, "1").is_err());
+    }
+
+    #[test]
+    fn test_parse_feedback() {
+        let parse = |page| submit::parse_feedback(page).unwrap();
+        assert_eq!(
+            parse("<p>That's the right answer!  You are one gold star closer.</p>"),
+            Feedback::Correct
+        );
+        assert_eq!(
+            parse("<p>That's not the right answer; your answer is too low.</p>"),
+            Feedback::TooLow
+        );
+        assert_eq!(
+            parse("<p>That's not the right answer.  If you're stuck, ...</p>"),
+            Feedback::Wrong
+        );
+        assert_eq!(
+            parse("<p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 3s left to wait.</p>"),
+            Feedback::Wait(Duration::from_secs(243))
+        );
+        assert_eq!(
+            parse("<p>You don't seem to be solving the right level.  Did you already complete it?</p>"),
+            Feedback::WrongLevel
+        );
+        assert!(submit::parse_feedback("<p>Hello</p>").is_err());
+    }
+}
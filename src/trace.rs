@@ -0,0 +1,182 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    str::FromStr,
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
+};
+
+// Intermediate solver state, written to stderr as "[2021 day 4 info] ...".
+// Off unless enabled from the command line; a disabled trace point costs a
+// couple of relaxed atomic loads, and its message is never formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    // A handful of lines per part: winners, totals, final ratings.
+    Info = 1,
+    // A line per step: every command, segment or filtering round.
+    Debug = 2,
+}
+
+impl FromStr for Level {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Info => write!(f, "info"),
+            Level::Debug => write!(f, "debug"),
+        }
+    }
+}
+
+// The seasons a trace point can be enabled for, from the first one on.
+const FIRST_YEAR: u16 = 2015;
+const YEARS: usize = 25;
+
+// The enabled level of each day of each season, 0 when off.
+static LEVELS: [AtomicU8; YEARS * 25] = [const { AtomicU8::new(0) }; YEARS * 25];
+
+fn slot(year: u16, day: u8) -> Option<usize> {
+    let season = year.checked_sub(FIRST_YEAR)? as usize;
+    if season >= YEARS || !(1..=25).contains(&day) {
+        return None;
+    }
+    Some(season * 25 + day as usize - 1)
+}
+
+// Threads inside `capture`, so the others can skip the thread-local lookup.
+static CAPTURING: AtomicUsize = AtomicUsize::new(0);
+
+struct Capture {
+    year: u16,
+    day: u8,
+    level: Level,
+    lines: Vec<String>,
+}
+
+thread_local! {
+    static CAPTURED: RefCell<Option<Capture>> = const { RefCell::new(None) };
+}
+
+// Enables the day of a season, or every day of every season for None, at
+// `level` and everything below it. Enabling never lowers a level already set.
+pub fn enable(day: Option<(u16, u8)>, level: Level) {
+    let slots = match day {
+        Some((year, day)) => match slot(year, day) {
+            Some(slot) => slot..slot + 1,
+            None => return,
+        },
+        None => 0..LEVELS.len(),
+    };
+    for slot in slots {
+        LEVELS[slot].fetch_max(level as u8, Ordering::Relaxed);
+    }
+}
+
+pub fn enabled(year: u16, day: u8, level: Level) -> bool {
+    let global = slot(year, day).is_some_and(|s| LEVELS[s].load(Ordering::Relaxed) >= level as u8);
+    global || CAPTURING.load(Ordering::Relaxed) > 0 && captured(year, day, level)
+}
+
+fn captured(year: u16, day: u8, level: Level) -> bool {
+    CAPTURED.with(|captured| {
+        captured
+            .borrow()
+            .as_ref()
+            .is_some_and(|c| c.year == year && c.day == day && c.level >= level)
+    })
+}
+
+pub fn log(year: u16, day: u8, level: Level, message: impl FnOnce() -> String) {
+    if !enabled(year, day, level) {
+        return;
+    }
+    let line = format!("[{} day {} {}] {}", year, day, level, message());
+    let line = CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(capture) => {
+            capture.lines.push(line);
+            None
+        }
+        None => Some(line),
+    });
+    if let Some(line) = line {
+        eprintln!("{}", line);
+    }
+}
+
+// Runs `f` with the day of `year` traced at `level` on this thread only, and
+// returns what was traced instead of printing it.
+pub fn capture<R>(year: u16, day: u8, level: Level, f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    let capture = Capture {
+        year,
+        day,
+        level,
+        lines: vec![],
+    };
+    CAPTURING.fetch_add(1, Ordering::Relaxed);
+    let outer = CAPTURED.with(|captured| captured.replace(Some(capture)));
+    let result = f();
+    let capture = CAPTURED.with(|captured| captured.replace(outer));
+    CAPTURING.fetch_sub(1, Ordering::Relaxed);
+    (result, capture.map_or(vec![], |c| c.lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trace::{self, Level};
+
+    #[test]
+    fn test_capture() {
+        let ((), lines) = trace::capture(2021, 24, Level::Info, || {
+            trace::log(2021, 24, Level::Info, || "board 2 wins".to_string());
+            trace::log(2021, 24, Level::Debug, || {
+                unreachable!("formatted while disabled")
+            });
+            trace::log(2021, 23, Level::Info, || {
+                unreachable!("formatted while disabled")
+            });
+            trace::log(2020, 24, Level::Info, || {
+                unreachable!("formatted while disabled")
+            });
+        });
+        assert_eq!(lines, vec!["[2021 day 24 info] board 2 wins"]);
+        assert!(!trace::enabled(2021, 24, Level::Info));
+
+        let (n, lines) = trace::capture(2021, 24, Level::Debug, || {
+            trace::log(2021, 24, Level::Debug, || "draw 24".to_string());
+            7
+        });
+        assert_eq!((n, lines.len()), (7, 1));
+    }
+
+    // Day 25 of 2016 is left to this test, so enabling it globally cannot
+    // leak into the trace points of the days tested in parallel.
+    #[test]
+    fn test_enable() {
+        assert!(!trace::enabled(2016, 25, Level::Info));
+        trace::enable(Some((2016, 25)), Level::Debug);
+        trace::enable(Some((2016, 25)), Level::Info);
+        assert!(trace::enabled(2016, 25, Level::Debug));
+        assert!(!trace::enabled(2017, 25, Level::Info));
+        assert!(!trace::enabled(2016, 26, Level::Info));
+        assert!(!trace::enabled(2016, 0, Level::Info));
+        assert!(!trace::enabled(2014, 25, Level::Info));
+        trace::enable(Some((1999, 25)), Level::Debug);
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!("debug".parse(), Ok(Level::Debug));
+        assert_eq!("verbose".parse::<Level>(), Err(()));
+        assert!(Level::Info < Level::Debug);
+        assert_eq!(Level::Info.to_string(), "info");
+    }
+}
@@ -1,21 +1,73 @@
-use std::time::{Duration, Instant};
+use std::{
+    any::Any,
+    collections::VecDeque,
+    error::Error,
+    hint::black_box,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-pub fn track_time(to_track: Box<dyn FnOnce()>) -> Duration {
+use crate::{
+    memory::{self, AllocStats},
+    registry::Parser,
+};
+
+pub fn track_time<'a, T>(to_track: Box<dyn FnOnce() -> T + 'a>) -> (T, Duration) {
     let now = Instant::now();
-    to_track();
-    now.elapsed()
+    let result = to_track();
+    (result, now.elapsed())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status<T> {
+    Done(T),
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
 }
 
-pub struct Puzzle {
+fn catch_panic<'a, T>(task: Box<dyn FnOnce() -> T + 'a>) -> Status<T> {
+    match panic::catch_unwind(AssertUnwindSafe(task)) {
+        Ok(result) => Status::Done(result),
+        Err(payload) => Status::Panicked(panic_message(&*payload)),
+    }
+}
+
+// Measured on the thread that runs the task, since allocations are counted
+// per thread.
+fn run_task<'a, T>(task: Box<dyn FnOnce() -> T + 'a>) -> (Status<T>, Option<AllocStats>) {
+    memory::measure(|| catch_panic(task))
+}
+
+pub struct Puzzle<T> {
     name: String,
     elapsed_time: Duration,
+    alloc_stats: Option<AllocStats>,
+    status: Status<T>,
 }
 
-impl Puzzle {
-    pub fn run(name: &str, task: Box<dyn FnOnce()>) -> Puzzle {
+impl<T> Puzzle<T> {
+    pub fn run<'a>(name: &str, task: Box<dyn FnOnce() -> T + 'a>) -> Puzzle<T> {
+        let ((status, alloc_stats), elapsed_time) = track_time(Box::new(move || run_task(task)));
         Puzzle {
             name: name.to_string(),
-            elapsed_time: track_time(task),
+            elapsed_time,
+            alloc_stats,
+            status,
         }
     }
 
@@ -26,4 +78,329 @@ impl Puzzle {
     pub fn elapsed_time(&self) -> &Duration {
         &self.elapsed_time
     }
+
+    pub fn status(&self) -> &Status<T> {
+        &self.status
+    }
+
+    pub fn into_status(self) -> Status<T> {
+        self.status
+    }
+
+    // None unless the counting allocator is installed, or when timed out.
+    pub fn alloc_stats(&self) -> Option<&AllocStats> {
+        self.alloc_stats.as_ref()
+    }
+}
+
+pub type Task<T> = Box<dyn FnOnce() -> T + Send>;
+
+impl<T: Send + 'static> Puzzle<T> {
+    // A puzzle that overruns is left running on its detached thread; only
+    // the report stops waiting for it.
+    pub fn run_with_timeout(name: &str, task: Task<T>, timeout: Duration) -> Puzzle<T> {
+        let now = Instant::now();
+        let (tx, rx) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                let _ = tx.send(run_task(task));
+            });
+        let (status, alloc_stats) = match spawned {
+            Ok(_) => match rx.recv_timeout(timeout) {
+                Ok(result) => result,
+                Err(RecvTimeoutError::Timeout) => (Status::TimedOut(timeout), None),
+                Err(RecvTimeoutError::Disconnected) => (
+                    Status::Panicked("puzzle thread exited without a result".to_string()),
+                    None,
+                ),
+            },
+            Err(err) => (
+                Status::Panicked(format!("cannot spawn puzzle thread: {}", err)),
+                None,
+            ),
+        };
+        Puzzle {
+            name: name.to_string(),
+            elapsed_time: now.elapsed(),
+            alloc_stats,
+            status,
+        }
+    }
+}
+
+// Runs every task on a pool of `jobs` worker threads and returns the
+// puzzles in the same order as `tasks`, whatever order they finish in.
+pub fn run_parallel<T: Send + 'static>(
+    tasks: Vec<(String, Task<T>)>,
+    jobs: usize,
+    timeout: Option<Duration>,
+) -> Vec<Puzzle<T>> {
+    let len = tasks.len();
+    let queue = Arc::new(Mutex::new(
+        tasks.into_iter().enumerate().collect::<VecDeque<_>>(),
+    ));
+    let (tx, rx) = mpsc::channel();
+    let workers = (0..jobs.clamp(1, len.max(1)))
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            let tx = tx.clone();
+            thread::spawn(move || loop {
+                let next = queue.lock().unwrap().pop_front();
+                let (i, (name, task)) = match next {
+                    Some(t) => t,
+                    None => break,
+                };
+                let puzzle = match timeout {
+                    Some(timeout) => Puzzle::run_with_timeout(&name, task, timeout),
+                    None => Puzzle::run(&name, task),
+                };
+                if tx.send((i, puzzle)).is_err() {
+                    break;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(tx);
+
+    let mut puzzles: Vec<Option<Puzzle<T>>> = (0..len).map(|_| None).collect();
+    for (i, puzzle) in rx {
+        puzzles[i] = Some(puzzle);
+    }
+    for worker in workers {
+        let _ = worker.join();
+    }
+    puzzles.into_iter().flatten().collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        if samples.is_empty() {
+            return Stats {
+                samples: 0,
+                min: Duration::ZERO,
+                median: Duration::ZERO,
+                mean: Duration::ZERO,
+                p95: Duration::ZERO,
+                stddev: Duration::ZERO,
+            };
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let p95_rank = (n * 95).div_ceil(100);
+        let mean = sorted.iter().sum::<Duration>() / n as u32;
+        let variance = sorted
+            .iter()
+            .map(|s| {
+                let d = s.as_secs_f64() - mean.as_secs_f64();
+                d * d
+            })
+            .sum::<f64>()
+            / n as f64;
+        Stats {
+            samples: n,
+            min: sorted[0],
+            median,
+            mean,
+            p95: sorted[p95_rank.max(1) - 1],
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+pub struct BenchResult {
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+pub fn bench(
+    parse: Parser,
+    input: &str,
+    warmup: usize,
+    iterations: usize,
+) -> Result<BenchResult, Box<dyn Error>> {
+    let mut parse_samples = Vec::with_capacity(iterations);
+    let mut part1_samples = Vec::with_capacity(iterations);
+    let mut part2_samples = Vec::with_capacity(iterations);
+    for i in 0..warmup + iterations {
+        let now = Instant::now();
+        let solution = parse(black_box(input))?;
+        let parse_time = now.elapsed();
+
+        let now = Instant::now();
+        black_box(solution.part1());
+        let part1_time = now.elapsed();
+
+        let now = Instant::now();
+        black_box(solution.part2());
+        let part2_time = now.elapsed();
+
+        if i >= warmup {
+            parse_samples.push(parse_time);
+            part1_samples.push(part1_time);
+            part2_samples.push(part2_time);
+        }
+    }
+    Ok(BenchResult {
+        parse: Stats::from_samples(&parse_samples),
+        part1: Stats::from_samples(&part1_samples),
+        part2: Stats::from_samples(&part2_samples),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        registry,
+        utils::{bench, run_parallel, Puzzle, Stats, Status, Task},
+    };
+
+    fn us(v: &[u64]) -> Vec<Duration> {
+        v.iter().map(|&a| Duration::from_micros(a)).collect()
+    }
+
+    #[test]
+    fn test_puzzle_run() {
+        let puzzle = Puzzle::run("ok", Box::new(|| 42));
+        assert_eq!(puzzle.name(), "ok");
+        assert_eq!(puzzle.status(), &Status::Done(42));
+    }
+
+    // The library's tests run under the counting allocator.
+    #[test]
+    fn test_puzzle_alloc_stats() {
+        let puzzle = Puzzle::run("vec", Box::new(|| vec![0_u64; 100].len()));
+        let stats = puzzle.alloc_stats().unwrap();
+        assert_eq!((stats.bytes, stats.peak, stats.count), (800, 800, 1));
+
+        let timeout = Duration::from_millis(100);
+        let puzzle = Puzzle::run_with_timeout("vec", Box::new(|| vec![0_u8; 10]), timeout);
+        assert_eq!(puzzle.alloc_stats().unwrap().bytes, 10);
+        let puzzle = Puzzle::run_with_timeout(
+            "slow",
+            Box::new(|| std::thread::sleep(Duration::from_secs(1))),
+            Duration::from_millis(10),
+        );
+        assert_eq!(puzzle.alloc_stats(), None);
+    }
+
+    #[test]
+    fn test_puzzle_run_panic() {
+        let puzzle: Puzzle<i32> = Puzzle::run("panic", Box::new(|| panic!("no way!")));
+        assert_eq!(puzzle.status(), &Status::Panicked("no way!".to_string()));
+
+        let window = 4;
+        let puzzle: Puzzle<i32> =
+            Puzzle::run("fmt", Box::new(move || panic!("window {} too big", window)));
+        assert_eq!(
+            puzzle.status(),
+            &Status::Panicked("window 4 too big".to_string())
+        );
+    }
+
+    #[test]
+    fn test_puzzle_run_with_timeout() {
+        let timeout = Duration::from_millis(20);
+        let puzzle = Puzzle::run_with_timeout("fast", Box::new(|| 7), timeout);
+        assert_eq!(puzzle.status(), &Status::Done(7));
+
+        let puzzle: Puzzle<i32> =
+            Puzzle::run_with_timeout("panic", Box::new(|| panic!("boom")), timeout);
+        assert_eq!(puzzle.status(), &Status::Panicked("boom".to_string()));
+
+        let puzzle = Puzzle::run_with_timeout(
+            "slow",
+            Box::new(|| std::thread::sleep(Duration::from_secs(2))),
+            timeout,
+        );
+        assert_eq!(puzzle.status(), &Status::TimedOut(timeout));
+        assert!(*puzzle.elapsed_time() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_run_parallel() {
+        let tasks: Vec<(String, Task<u64>)> = (0..8_u64)
+            .map(|i| {
+                let task: Task<u64> = Box::new(move || {
+                    std::thread::sleep(Duration::from_millis(8 - i));
+                    if i == 5 {
+                        panic!("task {}", i);
+                    }
+                    i * i
+                });
+                (format!("task{}", i), task)
+            })
+            .collect();
+        let puzzles = run_parallel(tasks, 3, None);
+        assert_eq!(puzzles.len(), 8);
+        for (i, puzzle) in puzzles.iter().enumerate() {
+            assert_eq!(puzzle.name(), format!("task{}", i));
+            if i == 5 {
+                assert_eq!(puzzle.status(), &Status::Panicked("task 5".to_string()));
+            } else {
+                assert_eq!(puzzle.status(), &Status::Done((i * i) as u64));
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_parallel_empty() {
+        let puzzles = run_parallel::<u8>(vec![], 4, None);
+        assert!(puzzles.is_empty());
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&us(&[5, 1, 3, 2, 4]));
+        assert_eq!(stats.samples, 5);
+        assert_eq!(stats.min, Duration::from_micros(1));
+        assert_eq!(stats.median, Duration::from_micros(3));
+        assert_eq!(stats.mean, Duration::from_micros(3));
+        assert_eq!(stats.p95, Duration::from_micros(5));
+        assert_eq!(stats.stddev.as_nanos(), 1414);
+    }
+
+    #[test]
+    fn test_stats_even_and_p95() {
+        let samples: Vec<u64> = (1..=100).collect();
+        let stats = Stats::from_samples(&us(&samples));
+        assert_eq!(stats.median, Duration::from_nanos(50_500));
+        assert_eq!(stats.p95, Duration::from_micros(95));
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        let stats = Stats::from_samples(&[]);
+        assert_eq!(stats.samples, 0);
+        assert_eq!(stats.min, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_bench() {
+        let day = registry::find(2021, 1).unwrap();
+        let result = bench(day.parse, "1\n2\n3", 2, 5).unwrap();
+        assert_eq!(result.parse.samples, 5);
+        assert_eq!(result.part1.samples, 5);
+        assert_eq!(result.part2.samples, 5);
+        assert!(bench(day.parse, "x", 0, 1).is_err());
+    }
 }
@@ -0,0 +1,69 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+// Modification time and length, so an edit within the file system's timestamp
+// granularity still shows up when the size changes. None for a missing file.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    stamps: Vec<Stamp>,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Watcher {
+        let stamps = paths.iter().map(|p| stamp(p)).collect();
+        Watcher { paths, stamps }
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    pub fn changed(&mut self) -> bool {
+        let stamps: Vec<Stamp> = self.paths.iter().map(|p| stamp(p)).collect();
+        let changed = stamps != self.stamps;
+        self.stamps = stamps;
+        changed
+    }
+
+    pub fn wait(&mut self, interval: Duration) {
+        while !self.changed() {
+            thread::sleep(interval);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process, time::Duration};
+
+    use crate::watch::Watcher;
+
+    #[test]
+    fn test_changed() {
+        let path = env::temp_dir().join(format!("aoc-watch-{}", process::id()));
+        let mut watcher = Watcher::new(vec![path.clone()]);
+        assert!(!watcher.changed());
+
+        fs::write(&path, "199\n").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        fs::write(&path, "199\n200\n").unwrap();
+        watcher.wait(Duration::from_millis(1));
+        assert!(!watcher.changed());
+
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.changed());
+    }
+}
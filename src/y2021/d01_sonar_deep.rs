@@ -0,0 +1,398 @@
+use std::{error::Error, io::BufRead};
+
+use crate::{
+    d00_aoc::{for_each_line, query_arg, Answer, InputReader, ParseError, Solution},
+    parse,
+    trace::{self, Level},
+};
+
+pub struct SonarDeep {
+    input: Vec<i32>,
+}
+
+impl InputReader<i32> for SonarDeep {
+    fn string_to_vector(input_str: String) -> Result<Vec<i32>, ParseError> {
+        parse::ints(1, &input_str)
+    }
+
+    fn from_reader<R: BufRead>(reader: R) -> Result<Self, Box<dyn Error>> {
+        let mut input = vec![];
+        for_each_line(reader, |n, line| {
+            input.push(parse::int_line(1, n, line)?);
+            Ok(())
+        })?;
+        Ok(SonarDeep { input })
+    }
+}
+
+impl Solution for SonarDeep {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        Self::from_reader(input.as_bytes())
+    }
+
+    fn part1(&self) -> Answer {
+        self.measurements().into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.measurements_window_sum(3).into()
+    }
+
+    fn queries(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("depth <N>", "the Nth measurement, counting from 1"),
+            (
+                "window <SIZE>",
+                "how many sums of SIZE measurements increase",
+            ),
+        ]
+    }
+
+    fn query(&self, name: &str, args: &[&str]) -> Option<Result<String, Box<dyn Error>>> {
+        let result = match name {
+            "depth" => query_arg::<usize>(args, 0, "N").and_then(|n| {
+                match n.checked_sub(1).and_then(|i| self.input.get(i)) {
+                    Some(depth) => Ok(depth.to_string()),
+                    None => Err(format!("there are {} measurements", self.input.len()).into()),
+                }
+            }),
+            "window" => query_arg::<usize>(args, 0, "SIZE").and_then(|size| match size {
+                0 => Err("the window must hold at least one measurement".into()),
+                _ => Ok(self.measurements_window_sum(size).to_string()),
+            }),
+            _ => return None,
+        };
+        Some(result)
+    }
+}
+
+impl SonarDeep {
+    pub fn new(input: Vec<i32>) -> SonarDeep {
+        SonarDeep { input }
+    }
+
+    // Counts the increases of every window without keeping the depths, for
+    // readings too many to hold in memory.
+    pub fn stream<R: BufRead>(
+        reader: R,
+        windows: &[usize],
+    ) -> Result<DepthIncreases, Box<dyn Error>> {
+        let mut increases = DepthIncreases::new(windows);
+        for_each_line(reader, |n, line| {
+            increases.push(parse::int_line(1, n, line)?);
+            Ok(())
+        })?;
+        for (window, count) in increases.counts() {
+            trace::log(2021, 1, Level::Info, || {
+                format!(
+                    "window of {}: {} increases in {} streamed depths",
+                    window,
+                    count,
+                    increases.seen()
+                )
+            });
+        }
+        Ok(increases)
+    }
+
+    pub fn measurements(&self) -> i32 {
+        let mut a = 0;
+        let input = &self.input;
+        for i in 1..input.len() {
+            if input[i] > input[i - 1] {
+                a += 1;
+            }
+        }
+        trace::log(2021, 1, Level::Info, || {
+            format!("{} of {} depths increase", a, input.len())
+        });
+        a
+    }
+
+    pub fn measurements_window_sum(&self, window: usize) -> i32 {
+        let input = &self.input;
+        let mut a = 0;
+        let mut last_acc = -1;
+        if input.len() < window {
+            return 0;
+        }
+        for i in 0..=input.len() - window {
+            let mut acc = 0;
+            for v in &input[i..i + window] {
+                acc += v
+            }
+            if last_acc > 0 && last_acc < acc {
+                a += 1;
+            }
+            trace::log(2021, 1, Level::Debug, || {
+                format!("window at {} sums to {}", i, acc)
+            });
+            last_acc = acc;
+        }
+        trace::log(2021, 1, Level::Info, || {
+            format!("{} sums of {} depths increase", a, window)
+        });
+        a
+    }
+}
+
+// Increase counts for several window sizes at once, fed one depth at a time.
+// A sum of `k` depths grows exactly when the depth entering the window is
+// deeper than the one `k` readings back leaving it, so each window keeps only
+// its count; the latest depths are kept once for all of them, in a ring as
+// long as the largest window.
+pub struct DepthIncreases {
+    windows: Vec<usize>,
+    counts: Vec<usize>,
+    ring: Vec<i32>,
+    seen: usize,
+}
+
+impl DepthIncreases {
+    pub fn new(windows: &[usize]) -> DepthIncreases {
+        assert!(
+            windows.iter().all(|&w| w > 0),
+            "a window holds at least one depth"
+        );
+        let largest = windows.iter().copied().max().unwrap_or(0);
+        DepthIncreases {
+            windows: windows.to_vec(),
+            counts: vec![0; windows.len()],
+            ring: vec![0; largest],
+            seen: 0,
+        }
+    }
+
+    pub fn push(&mut self, depth: i32) {
+        let len = self.ring.len();
+        if len > 0 {
+            for (&window, count) in self.windows.iter().zip(&mut self.counts) {
+                if self.seen >= window && depth > self.ring[(self.seen - window) % len] {
+                    *count += 1;
+                }
+            }
+            self.ring[self.seen % len] = depth;
+        }
+        self.seen += 1;
+    }
+
+    pub fn seen(&self) -> usize {
+        self.seen
+    }
+
+    // None when `window` is not one of the sizes counted.
+    pub fn increases(&self, window: usize) -> Option<usize> {
+        let i = self.windows.iter().position(|&w| w == window)?;
+        Some(self.counts[i])
+    }
+
+    // Window size and increase count, in the order the sizes were given.
+    pub fn counts(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.windows
+            .iter()
+            .copied()
+            .zip(self.counts.iter().copied())
+    }
+}
+
+impl Extend<i32> for DepthIncreases {
+    fn extend<I: IntoIterator<Item = i32>>(&mut self, depths: I) {
+        for depth in depths {
+            self.push(depth);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, BufReader, Read};
+
+    use crate::{
+        d00_aoc::{InputReader, Solution},
+        generate::{self, Rng},
+        memory,
+        trace::{self, Level},
+        y2021::d01_sonar_deep::{DepthIncreases, SonarDeep},
+    };
+
+    #[test]
+    fn test_sonar_deep() {
+        let sonar = SonarDeep::new(vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263]);
+        assert_eq!(sonar.measurements(), 7);
+    }
+
+    #[test]
+    fn test_sonar_deep_window() {
+        let sonar = SonarDeep::new(vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263]);
+        assert_eq!(sonar.measurements_window_sum(3), 5);
+    }
+
+    #[test]
+    fn test_sonar_deep_window_short_input() {
+        assert_eq!(SonarDeep::new(vec![122]).measurements_window_sum(3), 0);
+        assert_eq!(SonarDeep::new(vec![]).measurements_window_sum(3), 0);
+    }
+
+    #[test]
+    fn test_string_to_vec() {
+        let input = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        let str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+        let r = SonarDeep::string_to_vector(str.to_string()).unwrap();
+        assert_eq!(r.len(), input.len());
+        for i in 0..r.len() {
+            assert_eq!(r[i], input[i])
+        }
+    }
+
+    #[test]
+    fn test_string_to_vec_trailing_newline() {
+        let r = SonarDeep::string_to_vector("199\n200\n208\n".to_string()).unwrap();
+        assert_eq!(r, vec![199, 200, 208]);
+    }
+
+    #[test]
+    fn test_string_to_vec_malformed() {
+        let err = SonarDeep::string_to_vector("199\n2x0\n208".to_string()).unwrap_err();
+        assert_eq!(err.day, 1);
+        assert_eq!(err.line, 2);
+        assert_eq!(err.column, 1);
+        assert_eq!(err.text, "2x0");
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let sonar = SonarDeep::from_reader("199\r\n200\r\n208\r\n".as_bytes()).unwrap();
+        assert_eq!(sonar.input, vec![199, 200, 208]);
+
+        let err = SonarDeep::from_reader("199\n\n208".as_bytes())
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 1 input, line 2, column 1: expected an integer, found ''"
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let sonar = SonarDeep::new(vec![199, 200, 208, 210]);
+        let (_, lines) = trace::capture(2021, 1, Level::Debug, || sonar.measurements_window_sum(3));
+        assert_eq!(
+            lines,
+            vec![
+                "[2021 day 1 debug] window at 0 sums to 607",
+                "[2021 day 1 debug] window at 1 sums to 618",
+                "[2021 day 1 info] 1 sums of 3 depths increase",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query() {
+        let sonar = SonarDeep::new(vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263]);
+        assert_eq!(sonar.query("depth", &["3"]).unwrap().unwrap(), "208");
+        assert_eq!(sonar.query("window", &["3"]).unwrap().unwrap(), "5");
+        let err = sonar.query("depth", &["11"]).unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "there are 10 measurements");
+        assert!(sonar.query("window", &["0"]).unwrap().is_err());
+        assert!(sonar.query("sonar", &[]).is_none());
+    }
+
+    #[test]
+    fn test_depth_increases() {
+        let mut increases = DepthIncreases::new(&[1, 3, 20]);
+        increases.extend(vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263]);
+        assert_eq!(increases.seen(), 10);
+        assert_eq!(increases.increases(1), Some(7));
+        assert_eq!(increases.increases(3), Some(5));
+        assert_eq!(increases.increases(20), Some(0));
+        assert_eq!(increases.increases(2), None);
+        assert_eq!(
+            increases.counts().collect::<Vec<_>>(),
+            vec![(1, 7), (3, 5), (20, 0)]
+        );
+
+        let mut none = DepthIncreases::new(&[]);
+        none.extend([1, 2]);
+        assert_eq!((none.seen(), none.counts().count()), (2, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one depth")]
+    fn test_depth_increases_empty_window() {
+        DepthIncreases::new(&[3, 0]);
+    }
+
+    #[test]
+    fn test_depth_increases_against_stored() {
+        let input = generate::sonar_deep(&mut Rng::new(25), 2000);
+        let sonar = SonarDeep::parse(&input).unwrap();
+        let windows = [1, 2, 3, 7, 50];
+        let increases = SonarDeep::stream(input.as_bytes(), &windows).unwrap();
+        assert_eq!(increases.increases(1), Some(sonar.measurements() as usize));
+        for window in windows {
+            assert_eq!(
+                increases.increases(window),
+                Some(sonar.measurements_window_sum(window) as usize)
+            );
+        }
+    }
+
+    // Depths 0, 1, 2, ... 0, 1, 2, ... produced on demand, never held whole.
+    struct Readings {
+        line: usize,
+        lines: usize,
+        pending: Vec<u8>,
+    }
+
+    impl Read for Readings {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pending.is_empty() && self.line < self.lines {
+                self.pending = format!("{}\n", self.line % 100).into_bytes();
+                self.line += 1;
+            }
+            let n = buf.len().min(self.pending.len());
+            buf[..n].copy_from_slice(&self.pending[..n]);
+            self.pending.drain(..n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_stream_memory() {
+        let readings = Readings {
+            line: 0,
+            lines: 100_000,
+            pending: vec![],
+        };
+        let (increases, stats) =
+            memory::measure(|| SonarDeep::stream(BufReader::new(readings), &[1, 3, 100]).unwrap());
+        assert_eq!(increases.seen(), 100_000);
+        assert_eq!(increases.increases(1), Some(99_000));
+        assert_eq!(increases.increases(3), Some(97_000));
+        assert_eq!(increases.increases(100), Some(0));
+        assert!(stats.unwrap().peak < 16 * 1024);
+
+        let err = SonarDeep::stream("199\n2x0\n".as_bytes(), &[1])
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 1 input, line 2, column 1: expected an integer, found '2x0'"
+        );
+    }
+
+    #[test]
+    fn test_stream_trace() {
+        let (_, lines) = trace::capture(2021, 1, Level::Info, || {
+            SonarDeep::stream("199\n200\n208\n210\n".as_bytes(), &[1, 3])
+        });
+        assert_eq!(
+            lines,
+            vec![
+                "[2021 day 1 info] window of 1: 3 increases in 4 streamed depths",
+                "[2021 day 1 info] window of 3: 1 increases in 4 streamed depths",
+            ]
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use std::{error::Error, fmt, io::BufRead, str::FromStr};
+
+use crate::{
+    d00_aoc::{for_each_line, query_arg, Answer, InputReader, ParseError, Solution},
+    parse,
+    trace::{self, Level},
+};
+
+#[derive(PartialEq, Eq)]
+enum Command {
+    Forward = 0,
+    Down,
+    Up,
+}
+
+impl FromStr for Command {
+    type Err = ();
+
+    fn from_str(input_str: &str) -> Result<Self, Self::Err> {
+        match input_str {
+            "forward" => Ok(Command::Forward),
+            "down" => Ok(Command::Down),
+            "up" => Ok(Command::Up),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Debug for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let r = match self {
+            Command::Forward => "forward",
+            Command::Down => "down",
+            Command::Up => "up",
+        };
+        write!(f, "{}", r)
+    }
+}
+
+#[derive(Debug)]
+pub struct DiveCommand {
+    command: Command,
+    value: i32,
+}
+
+pub struct Dive {
+    commands: Vec<DiveCommand>,
+}
+
+impl InputReader<DiveCommand> for Dive {
+    fn string_to_vector(input_str: String) -> Result<Vec<DiveCommand>, ParseError> {
+        parse::lines(&input_str)
+            .map(|(n, line)| Self::parse_line(n, line))
+            .collect()
+    }
+
+    fn from_reader<R: BufRead>(reader: R) -> Result<Self, Box<dyn Error>> {
+        let mut commands = vec![];
+        for_each_line(reader, |n, line| {
+            commands.push(Self::parse_line(n, line)?);
+            Ok(())
+        })?;
+        Ok(Dive { commands })
+    }
+}
+
+impl Solution for Dive {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        Self::from_reader(input.as_bytes())
+    }
+
+    fn part1(&self) -> Answer {
+        (self.forward() * self.wrong_depth()).into()
+    }
+
+    fn part2(&self) -> Answer {
+        (self.forward() * self.depth()).into()
+    }
+
+    fn queries(&self) -> Vec<(&'static str, &'static str)> {
+        vec![(
+            "position <N>",
+            "position, depth and aim after the first N commands",
+        )]
+    }
+
+    fn query(&self, name: &str, args: &[&str]) -> Option<Result<String, Box<dyn Error>>> {
+        if name != "position" {
+            return None;
+        }
+        Some(query_arg::<usize>(args, 0, "N").and_then(|n| {
+            if n > self.commands.len() {
+                return Err(format!("there are {} commands", self.commands.len()).into());
+            }
+            let (position, depth, aim) = self.steer(n);
+            Ok(format!(
+                "position {}, depth {}, aim {}",
+                position, depth, aim
+            ))
+        }))
+    }
+}
+
+impl Dive {
+    fn parse_line(line_number: usize, line: &str) -> Result<DiveCommand, ParseError> {
+        let (command, value) = parse::command(2, line_number, line)?;
+        Ok(DiveCommand {
+            command: Command::from_str(command).map_err(|_| {
+                ParseError::at(2, line_number, line, command, "forward, down or up")
+            })?,
+            value,
+        })
+    }
+
+    pub fn new(commands: Vec<DiveCommand>) -> Dive {
+        Dive { commands }
+    }
+
+    // Sums and products in i64: a long input overflows i32 long before its
+    // commands do.
+    pub fn forward(&self) -> i64 {
+        self.commands
+            .iter()
+            .filter(|a| a.command == Command::Forward)
+            .map(|a| a.value as i64)
+            .sum()
+    }
+
+    pub fn wrong_depth(&self) -> i64 {
+        self.commands
+            .iter()
+            .filter(|a| a.command != Command::Forward)
+            .map(|a| {
+                if a.command == Command::Up {
+                    -a.value as i64
+                } else {
+                    a.value as i64
+                }
+            })
+            .sum()
+    }
+
+    pub fn depth(&self) -> i64 {
+        self.steer(self.commands.len()).1
+    }
+
+    // Position, depth and aim after the first `n` commands.
+    fn steer(&self, n: usize) -> (i64, i64, i64) {
+        let mut depth = 0;
+        let mut aim = 0;
+        let mut position = 0;
+        for a in &self.commands[..n] {
+            let value = a.value as i64;
+            match a.command {
+                Command::Up => aim -= value,
+                Command::Down => aim += value,
+                Command::Forward => {
+                    depth += aim * value;
+                    position += value;
+                }
+            }
+            trace::log(2021, 2, Level::Debug, || {
+                format!(
+                    "{:?} {}: position {}, depth {}, aim {}",
+                    a.command, a.value, position, depth, aim
+                )
+            });
+        }
+        trace::log(2021, 2, Level::Info, || {
+            format!("ends at position {}, depth {}", position, depth)
+        });
+        (position, depth, aim)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        d00_aoc::{Answer, InputReader, Solution},
+        trace::{self, Level},
+        y2021::d02_dive::{Command, Dive, DiveCommand},
+    };
+
+    fn get_commands() -> Vec<DiveCommand> {
+        // forward 5
+        // down 5
+        // forward 8
+        // up 3
+        // down 8
+        // forward 2
+        vec![
+            DiveCommand {
+                command: Command::Forward,
+                value: 5,
+            },
+            DiveCommand {
+                command: Command::Down,
+                value: 5,
+            },
+            DiveCommand {
+                command: Command::Forward,
+                value: 8,
+            },
+            DiveCommand {
+                command: Command::Up,
+                value: 3,
+            },
+            DiveCommand {
+                command: Command::Down,
+                value: 8,
+            },
+            DiveCommand {
+                command: Command::Forward,
+                value: 2,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_dive_forward() {
+        let dive_commands = get_commands();
+        let dive = Dive::new(dive_commands);
+        assert_eq!(dive.forward(), 15);
+    }
+
+    #[test]
+    fn test_dive_wrong_depth() {
+        let dive_commands = get_commands();
+        let dive = Dive::new(dive_commands);
+        assert_eq!(dive.wrong_depth(), 10);
+    }
+
+    #[test]
+    fn test_dive_depth() {
+        let dive_commands = get_commands();
+        let dive = Dive::new(dive_commands);
+        assert_eq!(dive.depth(), 60);
+    }
+
+    #[test]
+    fn test_past_i32() {
+        let dive = Dive::parse("down 50000\nforward 50000\n").unwrap();
+        assert_eq!(dive.part1(), Answer::Integer(2_500_000_000));
+        assert_eq!(dive.part2(), Answer::Integer(125_000_000_000_000));
+    }
+
+    #[test]
+    fn test_string_to_vec() {
+        let input = get_commands();
+        let str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
+        let r = Dive::string_to_vector(str.to_string()).unwrap();
+        assert_eq!(r.len(), input.len());
+        for i in 0..r.len() {
+            assert_eq!(r[i].command, input[i].command);
+            assert_eq!(r[i].value, input[i].value);
+        }
+    }
+
+    #[test]
+    fn test_string_to_vec_trailing_newline() {
+        let r = Dive::string_to_vector("forward 5\ndown 5\n".to_string()).unwrap();
+        assert_eq!(r.len(), 2);
+    }
+
+    #[test]
+    fn test_string_to_vec_malformed() {
+        let err = Dive::string_to_vector("forward 5\nbackward 5".to_string()).unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (2, 2, 1));
+        assert_eq!(err.text, "backward");
+
+        let err = Dive::string_to_vector("forward 5\ndown x5".to_string()).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 6));
+        assert_eq!(err.text, "x5");
+
+        let err = Dive::string_to_vector("up".to_string()).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+
+        let err = Dive::string_to_vector("up 1 2".to_string()).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 6));
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let dive =
+            Dive::from_reader("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n".as_bytes())
+                .unwrap();
+        assert_eq!(dive.commands.len(), 6);
+        assert_eq!(dive.depth(), 60);
+
+        let err = Dive::from_reader("forward 5\nsideways 5".as_bytes())
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 2 input, line 2, column 1: expected forward, down or up, found 'sideways'"
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let dive = Dive::new(get_commands());
+        let (_, lines) = trace::capture(2021, 2, Level::Debug, || dive.depth());
+        assert_eq!(lines.len(), 7);
+        assert_eq!(
+            lines[2],
+            "[2021 day 2 debug] forward 8: position 13, depth 40, aim 5"
+        );
+        assert_eq!(lines[6], "[2021 day 2 info] ends at position 15, depth 60");
+
+        let (_, lines) = trace::capture(2021, 2, Level::Info, || dive.depth());
+        assert_eq!(lines.len(), 1);
+    }
+
+    #[test]
+    fn test_query() {
+        let dive = Dive::new(get_commands());
+        assert_eq!(
+            dive.query("position", &["3"]).unwrap().unwrap(),
+            "position 13, depth 40, aim 5"
+        );
+        assert_eq!(
+            dive.query("position", &["0"]).unwrap().unwrap(),
+            "position 0, depth 0, aim 0"
+        );
+        let err = dive.query("position", &[]).unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "missing argument <N>");
+        assert!(dive.query("position", &["7"]).unwrap().is_err());
+    }
+}
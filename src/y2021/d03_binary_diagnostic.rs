@@ -0,0 +1,372 @@
+use std::{error::Error, io::BufRead};
+
+use crate::{
+    d00_aoc::{for_each_line, Answer, InputReader, ParseError, Solution},
+    parse,
+    trace::{self, Level},
+};
+
+pub struct BinaryDiagnostic {
+    gamma: u32,
+    epsilon: u32,
+    oxygen: u32,
+    co2: u32,
+}
+
+impl InputReader<Vec<u32>> for BinaryDiagnostic {
+    fn string_to_vector(input_str: String) -> Result<Vec<Vec<u32>>, ParseError> {
+        let input_vec = parse::digit_grid(3, &input_str, 2)?;
+        if input_vec.is_empty() {
+            return Err(Self::no_numbers());
+        }
+        Ok(input_vec)
+    }
+
+    fn from_reader<R: BufRead>(reader: R) -> Result<Self, Box<dyn Error>> {
+        let mut binary: Vec<Vec<u32>> = vec![];
+        for_each_line(reader, |n, line| {
+            let width = binary.first().map(|f| f.len());
+            binary.push(parse::digit_row(3, n, line, 2, width)?);
+            Ok(())
+        })?;
+        if binary.is_empty() {
+            return Err(Self::no_numbers().into());
+        }
+        Ok(BinaryDiagnostic::new(binary))
+    }
+}
+
+impl Solution for BinaryDiagnostic {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        Self::from_reader(input.as_bytes())
+    }
+
+    fn part1(&self) -> Answer {
+        // Ratings of wide numbers multiply past u32.
+        (self.gamma() as i64 * self.epsilon() as i64).into()
+    }
+
+    fn part2(&self) -> Answer {
+        (self.oxygen() as i64 * self.co2() as i64).into()
+    }
+
+    fn queries(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("ratings", "gamma, epsilon, oxygen and CO2 ratings")]
+    }
+
+    fn query(&self, name: &str, _args: &[&str]) -> Option<Result<String, Box<dyn Error>>> {
+        if name != "ratings" {
+            return None;
+        }
+        Some(Ok(format!(
+            "gamma {}, epsilon {}, oxygen {}, co2 {}",
+            self.gamma, self.epsilon, self.oxygen, self.co2
+        )))
+    }
+}
+
+impl BinaryDiagnostic {
+    fn no_numbers() -> ParseError {
+        ParseError::at(3, 1, "", "", "a binary number")
+    }
+
+    fn get_trick(rating: &str, search: Vec<Vec<u32>>, compare: fn(a: i32, b: i32) -> u32) -> u32 {
+        let len = search[0].len();
+        let mut search = search;
+        for i in 0..len {
+            let search_len = search.len() as i32;
+            if search.len() > 1 {
+                let h = search.iter().fold(vec![0; len], |mut acc, item| {
+                    for i in 0..len {
+                        acc[i] += item[i] as i32;
+                    }
+                    acc
+                });
+                let bit = compare(h[i], search_len - h[i]);
+                // When every number shares this bit, filtering would drop them
+                // all; keep them for the next bit instead.
+                if search.iter().any(|b| b[i] == bit) {
+                    search.retain(|b| b[i] == bit);
+                }
+                trace::log(2021, 3, Level::Debug, || {
+                    format!(
+                        "{}: bit {} keeps {}s, {} candidates left",
+                        rating,
+                        i,
+                        bit,
+                        search.len()
+                    )
+                });
+            }
+        }
+
+        let trick = search[0]
+            .iter()
+            .enumerate()
+            .map(|(i, a)| -> i32 {
+                let x = (len - 1 - i) as u32;
+                if a > &0 {
+                    2_i32.pow(x)
+                } else {
+                    0
+                }
+            })
+            .sum::<i32>() as u32;
+        trace::log(2021, 3, Level::Info, || {
+            format!("{} rating {}", rating, trick)
+        });
+        trick
+    }
+
+    // Every number must be as wide as the first, as both readers check. An
+    // empty diagnostic has no ratings to speak of, so they are all 0.
+    pub fn new(diagnostic: Vec<Vec<u32>>) -> BinaryDiagnostic {
+        if diagnostic.is_empty() {
+            return BinaryDiagnostic {
+                gamma: 0,
+                epsilon: 0,
+                oxygen: 0,
+                co2: 0,
+            };
+        }
+        let len = diagnostic[0].len() as i32;
+        let diagnostic_len = diagnostic.len() as i32 / 2;
+        let init = vec![0_i32; len as usize];
+        let mut epsilon = 0;
+
+        let hist = diagnostic.iter().fold(init, |mut acc, item| {
+            for i in 0..len {
+                let i = i as usize;
+                acc[i] += item[i] as i32;
+            }
+            acc
+        });
+
+        let gamma = hist
+            .iter()
+            .enumerate()
+            .map(|(i, a)| -> i32 {
+                let x = (len - 1_i32 - i as i32) as u32;
+                if a > &diagnostic_len {
+                    2_i32.pow(x)
+                } else {
+                    epsilon += 2_i32.pow(x);
+                    0
+                }
+            })
+            .sum::<i32>() as u32;
+        let epsilon = epsilon as u32;
+        trace::log(2021, 3, Level::Info, || {
+            format!("gamma {}, epsilon {}", gamma, epsilon)
+        });
+
+        let oxygen = Self::get_trick(
+            "oxygen",
+            diagnostic.clone(),
+            |a, b| {
+                if a >= b {
+                    1
+                } else {
+                    0
+                }
+            },
+        );
+
+        let co2 = Self::get_trick("co2", diagnostic.clone(), |a, b| if a >= b { 0 } else { 1 });
+
+        BinaryDiagnostic {
+            gamma,
+            epsilon,
+            oxygen,
+            co2,
+        }
+    }
+
+    pub fn gamma(&self) -> u32 {
+        self.gamma
+    }
+
+    pub fn epsilon(&self) -> u32 {
+        self.epsilon
+    }
+
+    pub fn oxygen(&self) -> u32 {
+        self.oxygen
+    }
+
+    pub fn co2(&self) -> u32 {
+        self.co2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        d00_aoc::{Answer, InputReader, Solution},
+        trace::{self, Level},
+        y2021::d03_binary_diagnostic::BinaryDiagnostic,
+    };
+
+    #[test]
+    fn test_string_to_vec() {
+        let s = String::from(
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010",
+        );
+        let e = vec![
+            vec![0, 0, 1, 0, 0_u32],
+            vec![1, 1, 1, 1, 0_u32],
+            vec![1, 0, 1, 1, 0_u32],
+            vec![1, 0, 1, 1, 1_u32],
+            vec![1, 0, 1, 0, 1_u32],
+            vec![0, 1, 1, 1, 1_u32],
+            vec![0, 0, 1, 1, 1_u32],
+            vec![1, 1, 1, 0, 0_u32],
+            vec![1, 0, 0, 0, 0_u32],
+            vec![1, 1, 0, 0, 1_u32],
+            vec![0, 0, 0, 1, 0_u32],
+            vec![0, 1, 0, 1, 0_u32],
+        ];
+        let v = BinaryDiagnostic::string_to_vector(s).unwrap();
+        assert_eq!(e.len(), v.len());
+        for i in 0..e.len() {
+            assert_eq!(e[i].len(), v[i].len());
+            for j in 0..e[i].len() {
+                assert_eq!(e[i][j], v[i][j])
+            }
+        }
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let s = String::from(
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010",
+        );
+        let v = BinaryDiagnostic::string_to_vector(s).unwrap();
+        let diagnostic = BinaryDiagnostic::new(v);
+        assert_eq!(diagnostic.gamma(), 22);
+        assert_eq!(diagnostic.epsilon(), 9);
+    }
+
+    #[test]
+    fn test_diagnostic_2() {
+        let s = String::from(
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010",
+        );
+        let v = BinaryDiagnostic::string_to_vector(s).unwrap();
+        let diagnostic = BinaryDiagnostic::new(v);
+        assert_eq!(diagnostic.oxygen(), 23);
+        assert_eq!(diagnostic.co2(), 10);
+    }
+
+    #[test]
+    fn test_diagnostic_shared_bits() {
+        let v = BinaryDiagnostic::string_to_vector(String::from("01011\n01010")).unwrap();
+        let diagnostic = BinaryDiagnostic::new(v);
+        assert_eq!(diagnostic.oxygen(), 11);
+        assert_eq!(diagnostic.co2(), 10);
+    }
+
+    #[test]
+    fn test_past_u32() {
+        let input = format!(
+            "{0}{1}\n{0}{2}1\n{3}\n",
+            "1".repeat(16),
+            "0".repeat(14),
+            "0".repeat(13),
+            "0".repeat(30)
+        );
+        let diagnostic = BinaryDiagnostic::parse(&input).unwrap();
+        assert_eq!(diagnostic.part1(), Answer::Integer(17_590_843_883_520));
+        assert_eq!(diagnostic.part2(), Answer::Integer(0));
+    }
+
+    #[test]
+    fn test_string_to_vec_trailing_newline() {
+        let v = BinaryDiagnostic::string_to_vector(String::from("00100\n11110\n")).unwrap();
+        assert_eq!(v, vec![vec![0, 0, 1, 0, 0], vec![1, 1, 1, 1, 0]]);
+    }
+
+    #[test]
+    fn test_string_to_vec_malformed() {
+        let err = BinaryDiagnostic::string_to_vector(String::from("00100\n11210")).unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (3, 2, 3));
+        assert_eq!(err.text, "2");
+
+        let err = BinaryDiagnostic::string_to_vector(String::from("00100\n1111")).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.text, "1111");
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let input =
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+        let diagnostic = BinaryDiagnostic::from_reader(input.as_bytes()).unwrap();
+        assert_eq!(diagnostic.gamma(), 22);
+        assert_eq!(diagnostic.co2(), 10);
+
+        let err = BinaryDiagnostic::from_reader("00100\n0010".as_bytes())
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 3 input, line 2, column 1: expected a row as wide as the first, found '0010'"
+        );
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let err = BinaryDiagnostic::string_to_vector(String::new()).unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (3, 1, 1));
+        assert_eq!(err.expected, "a binary number");
+
+        let err = BinaryDiagnostic::parse("").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 3 input, line 1, column 1: expected a binary number, found ''"
+        );
+
+        let err = BinaryDiagnostic::string_to_vector(String::from("\n\n")).unwrap_err();
+        assert_eq!((err.line, err.expected), (1, "a binary digit"));
+        let err = BinaryDiagnostic::parse("00100\n\n").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 3 input, line 2, column 1: expected a binary digit, found ''"
+        );
+
+        let diagnostic = BinaryDiagnostic::new(vec![]);
+        assert_eq!(
+            (diagnostic.part1(), diagnostic.part2()),
+            (Answer::Integer(0), Answer::Integer(0))
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let s = String::from(
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010",
+        );
+        let v = BinaryDiagnostic::string_to_vector(s).unwrap();
+        let (_, lines) = trace::capture(2021, 3, Level::Debug, || BinaryDiagnostic::new(v));
+        assert_eq!(lines[0], "[2021 day 3 info] gamma 22, epsilon 9");
+        assert_eq!(
+            lines[1],
+            "[2021 day 3 debug] oxygen: bit 0 keeps 1s, 7 candidates left"
+        );
+        assert!(lines.contains(&"[2021 day 3 info] oxygen rating 23".to_string()));
+        assert_eq!(lines.last().unwrap(), "[2021 day 3 info] co2 rating 10");
+    }
+
+    #[test]
+    fn test_query() {
+        let diagnostic = BinaryDiagnostic::parse(
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010",
+        )
+        .unwrap();
+        assert_eq!(
+            diagnostic.query("ratings", &[]).unwrap().unwrap(),
+            "gamma 22, epsilon 9, oxygen 23, co2 10"
+        );
+        assert!(diagnostic.query("gamma", &[]).is_none());
+    }
+}
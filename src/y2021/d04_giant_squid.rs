@@ -0,0 +1,520 @@
+use std::{collections::HashMap, error::Error, io::BufRead};
+
+use crate::{
+    d00_aoc::{for_each_line, query_arg, Answer, InputReader, ParseError, Solution},
+    grid::{Grid, Point},
+    parse,
+    trace::{self, Level},
+};
+
+type Board = Grid<i32>;
+
+// A board with the cells drawn so far, and where each of its numbers sits.
+#[derive(Clone)]
+struct Card {
+    board: Board,
+    taken: Grid<bool>,
+    places: HashMap<i32, Point>,
+}
+
+#[derive(Clone)]
+pub struct GiantSquid {
+    numbers: Vec<i32>,
+    boards: Vec<Card>,
+    winners: Vec<(usize, i32)>,
+}
+
+impl InputReader<Board> for GiantSquid {
+    fn string_to_vector(input_str: String) -> Result<Vec<Board>, ParseError> {
+        parse::blocks(&input_str)
+            .into_iter()
+            .map(|block| {
+                block
+                    .into_iter()
+                    .filter(|&(n, _)| n > 1)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|block| !block.is_empty())
+            .map(|block| Self::board(&block))
+            .collect()
+    }
+
+    // Holds on to one board's lines at a time.
+    fn from_reader<R: BufRead>(reader: R) -> Result<Self, Box<dyn Error>> {
+        let mut numbers = None;
+        let mut boards = vec![];
+        let mut block: Vec<(usize, String)> = vec![];
+        let mut push_board = |block: &mut Vec<(usize, String)>| -> Result<(), ParseError> {
+            if !block.is_empty() {
+                let lines: Vec<(usize, &str)> =
+                    block.iter().map(|(n, line)| (*n, line.as_str())).collect();
+                boards.push(Self::board(&lines)?);
+                block.clear();
+            }
+            Ok(())
+        };
+        for_each_line(reader, |n, line| {
+            if n == 1 {
+                numbers = Some(parse::comma_list(4, 1, line)?);
+            } else if line.trim().is_empty() {
+                push_board(&mut block)?;
+            } else {
+                block.push((n, line.to_string()));
+            }
+            Ok(())
+        })?;
+        push_board(&mut block)?;
+        let numbers = numbers.ok_or_else(|| ParseError::at(4, 1, "", "", "the drawn numbers"))?;
+        Ok(GiantSquid::new(numbers, boards))
+    }
+}
+
+impl Solution for GiantSquid {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        Self::from_reader(input.as_bytes())
+    }
+
+    fn part1(&self) -> Answer {
+        self.clone().find_first_winner_code().into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.clone().find_last_winner_code().into()
+    }
+
+    fn queries(&self) -> Vec<(&'static str, &'static str)> {
+        vec![(
+            "state <N>",
+            "every board after the first N draws, drawn numbers starred",
+        )]
+    }
+
+    fn query(&self, name: &str, args: &[&str]) -> Option<Result<String, Box<dyn Error>>> {
+        if name != "state" {
+            return None;
+        }
+        Some(query_arg::<usize>(args, 0, "N").and_then(|n| {
+            if n > self.numbers.len() {
+                return Err(format!("there are {} draws", self.numbers.len()).into());
+            }
+            Ok(self.state(n))
+        }))
+    }
+}
+
+impl GiantSquid {
+    // Five rows of five numbers, from a block of lines between blank ones.
+    fn board(block: &[(usize, &str)]) -> Result<Board, ParseError> {
+        if let Some(&(n, line)) = block.get(5) {
+            return Err(ParseError::at(
+                4,
+                n,
+                line,
+                line,
+                "a blank line between boards",
+            ));
+        }
+        for &(n, line) in block {
+            let error = |token, expected| ParseError::at(4, n, line, token, expected);
+            if let Some(extra) = line.split_whitespace().nth(5) {
+                return Err(error(extra, "end of row"));
+            }
+            if line.split_whitespace().count() < 5 {
+                return Err(error(&line[line.len()..], "5 numbers per row"));
+            }
+        }
+        let rows = parse::number_grid(4, block)?;
+        if rows.len() < 5 {
+            let n = block.last().map_or(2, |&(n, _)| n + 1);
+            return Err(ParseError::at(4, n, "", "", "a board row"));
+        }
+        Ok(Grid::from_rows(rows))
+    }
+
+    fn new(numbers: Vec<i32>, boards: Vec<Board>) -> GiantSquid {
+        let b = boards
+            .into_iter()
+            .map(|board| Card {
+                taken: Grid::dense(board.width(), board.height()),
+                places: board.iter().map(|(p, &value)| (value, p)).collect(),
+                board,
+            })
+            .collect();
+        GiantSquid {
+            numbers,
+            boards: b,
+            winners: vec![],
+        }
+    }
+
+    // Like too short a draw, no board ever winning leaves no score: -1.
+    pub fn find_first_winner_code(&mut self) -> i32 {
+        if self.numbers.len() < 7 {
+            return -1;
+        }
+        for i in 0..5 {
+            let n = self.numbers[i];
+            self.boards.iter_mut().for_each(|card| {
+                if let Some(&p) = card.places.get(&n) {
+                    card.taken[p] = true
+                }
+            });
+        }
+
+        let mut i = 5;
+        while i < self.numbers.len() {
+            let n = self.numbers[i];
+            self.boards.iter_mut().for_each(|card| {
+                if let Some(&p) = card.places.get(&n) {
+                    card.taken[p] = true
+                }
+            });
+            i += 1;
+            let board_winner = match self.check_winner(n) {
+                Some(winners) => winners[0],
+                None => continue,
+            };
+            trace::log(2021, 4, Level::Info, || {
+                format!("board {} wins first, on draw {}", board_winner, n)
+            });
+            return n * self.get_not_taken(board_winner);
+        }
+        -1
+    }
+
+    pub fn find_last_winner_code(&mut self) -> i32 {
+        if self.numbers.len() < 7 {
+            return -1;
+        }
+        for i in 0..5 {
+            let n = self.numbers[i];
+            self.boards.iter_mut().for_each(|card| {
+                if let Some(&p) = card.places.get(&n) {
+                    card.taken[p] = true
+                }
+            });
+        }
+
+        let mut i = 5;
+        let mut n = 0;
+        while i < self.numbers.len() {
+            n = self.numbers[i];
+            self.boards.iter_mut().for_each(|card| {
+                if let Some(&p) = card.places.get(&n) {
+                    card.taken[p] = true
+                }
+            });
+            i += 1;
+            let winner = self.check_winner(n);
+            if winner.is_none() {
+                continue;
+            };
+            winner.unwrap().iter_mut().for_each(|a| {
+                let i = *a;
+                self.winners.push((i, self.get_not_taken(i)));
+                trace::log(2021, 4, Level::Debug, || {
+                    format!("board {} wins on draw {}", i, n)
+                });
+            });
+            if self.winners.len() == self.boards.len() {
+                break;
+            }
+        }
+        let (board, unmarked) = match self.winners.last() {
+            Some(&winner) => winner,
+            None => return -1,
+        };
+        trace::log(2021, 4, Level::Info, || {
+            format!(
+                "board {} wins last, on draw {}, with {} unmarked",
+                board, n, unmarked
+            )
+        });
+        n * unmarked
+    }
+
+    fn check_winner(&self, n: i32) -> Option<Vec<usize>> {
+        let mut result = vec![];
+
+        self.boards.iter().enumerate().for_each(|(i, card)| {
+            if self.winners.iter().find(|a| a.0 == i).is_some() {
+                return;
+            }
+            let p = match card.places.get(&n) {
+                Some(&p) => p,
+                None => return,
+            };
+            let row = card.taken.row(p.y).all(|(_, &taken)| taken);
+            let column = card.taken.column(p.x).all(|(_, &taken)| taken);
+            if row || column {
+                result.push(i);
+            }
+        });
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
+    }
+
+    // The boards as they stand after the first `draws` numbers, whichever
+    // have won marked as such.
+    fn state(&self, draws: usize) -> String {
+        let mut out = match draws {
+            0 => "nothing drawn yet\n".to_string(),
+            _ => format!(
+                "{} drawn, the last one {}\n",
+                draws,
+                self.numbers[draws - 1]
+            ),
+        };
+        for (b, card) in self.boards.iter().enumerate() {
+            let mut taken = card.taken.clone();
+            for n in &self.numbers[..draws] {
+                if let Some(&p) = card.places.get(n) {
+                    taken[p] = true;
+                }
+            }
+            let won = (0..taken.height() as i64).any(|y| taken.row(y).all(|(_, &t)| t))
+                || (0..taken.width() as i64).any(|x| taken.column(x).all(|(_, &t)| t));
+            let mut board: Grid<String> = Grid::dense(card.board.width(), card.board.height());
+            for (p, value) in card.board.iter() {
+                board[p] = match taken[p] {
+                    true => format!("*{}", value),
+                    false => value.to_string(),
+                };
+            }
+            out += &format!(
+                "\nboard {}{}\n{}",
+                b,
+                if won { " (won)" } else { "" },
+                board
+            );
+        }
+        out
+    }
+
+    fn get_not_taken(&self, board_winner: usize) -> i32 {
+        let card = &self.boards[board_winner];
+        card.board
+            .iter()
+            .filter(|&(p, _)| !card.taken[p])
+            .fold(0, |acc, (_, value)| acc + value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{
+        d00_aoc::{Answer, InputReader, Solution},
+        grid::{Grid, Point},
+        trace::{self, Level},
+    };
+
+    use super::GiantSquid;
+
+    fn get_board() -> GiantSquid {
+        let numbers = vec![
+            7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21, 24, 10, 16, 13, 6, 15, 25, 12, 22, 18, 20, 8, 19,
+            3, 26, 1,
+        ];
+
+        let boards = [
+            [
+                [22, 13, 17, 11, 0],
+                [8, 2, 23, 4, 24],
+                [21, 9, 14, 16, 7],
+                [6, 10, 3, 18, 5],
+                [1, 12, 20, 15, 19],
+            ],
+            [
+                [3, 15, 0, 2, 22],
+                [9, 18, 13, 17, 5],
+                [19, 8, 7, 25, 23],
+                [20, 11, 10, 24, 4],
+                [14, 21, 16, 12, 6],
+            ],
+            [
+                [14, 21, 17, 24, 4],
+                [10, 16, 15, 9, 19],
+                [18, 8, 23, 26, 20],
+                [22, 11, 13, 6, 5],
+                [2, 0, 12, 3, 7],
+            ],
+        ];
+        let boards = boards
+            .iter()
+            .map(|b| Grid::from_rows(b.iter().map(|row| row.to_vec()).collect()))
+            .collect();
+        GiantSquid::new(numbers, boards)
+    }
+
+    fn get_string_board() -> String {
+        String::from(
+            r#"7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+
+22 13 17 11  0
+8  2 23  4 24
+21  9 14 16  7
+6 10  3 18  5
+1 12 20 15 19
+
+3 15  0  2 22
+9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+2  0 12  3  7"#,
+        )
+    }
+
+    #[test]
+    fn test_first_winner() {
+        let mut squid = get_board();
+        let result = squid.find_first_winner_code();
+        assert_eq!(result, 4512);
+    }
+
+    #[test]
+    fn test_last_winner() {
+        let mut squid = get_board();
+        let result = squid.find_last_winner_code();
+        assert_eq!(result, 1924);
+    }
+
+    #[test]
+    fn test_string_to_vec() {
+        let s = get_string_board();
+        let s_boards = GiantSquid::string_to_vector(s).unwrap();
+
+        let squid = get_board();
+        let boards = squid.boards;
+
+        assert_eq!(boards.len(), s_boards.len());
+        for k in 0..boards.len() {
+            assert_eq!(boards[k].board, s_boards[k]);
+        }
+    }
+
+    #[test]
+    fn test_input_read() {
+        let squid_load =
+            GiantSquid::from_file(Path::new("src/test_inputs/2021/d04_test_input.txt")).unwrap();
+
+        let squid = get_board();
+        let boards = squid.boards;
+        let s_boards = squid_load.boards;
+
+        assert_eq!(squid.numbers.len(), squid_load.numbers.len());
+        for k in 0..boards.len() {
+            assert_eq!(squid.numbers[k], squid_load.numbers[k]);
+        }
+        assert_eq!(boards.len(), s_boards.len());
+        for k in 0..boards.len() {
+            assert_eq!(boards[k].board, s_boards[k].board);
+        }
+        assert_eq!(s_boards[1].board[Point::new(3, 2)], 25);
+        assert_eq!(
+            s_boards[0].board.to_string().lines().nth(1),
+            Some(" 8  2 23  4 24")
+        );
+    }
+
+    #[test]
+    fn test_solution() {
+        let squid = GiantSquid::parse(&get_string_board()).unwrap();
+        assert_eq!(squid.part1(), Answer::Integer(4512));
+        assert_eq!(squid.part2(), Answer::Integer(1924));
+        assert_eq!(squid.part1(), Answer::Integer(4512));
+    }
+
+    #[test]
+    fn test_no_winner() {
+        let board = "1 2 3 4 5\n6 7 8 9 10\n11 12 13 14 15\n16 17 18 19 20\n21 22 23 24 25\n";
+        let squid = GiantSquid::parse(&format!("1,7,13,19,25,2,8\n\n{}", board)).unwrap();
+        assert_eq!(squid.part1(), Answer::Integer(-1));
+        assert_eq!(squid.part2(), Answer::Integer(-1));
+        let squid = GiantSquid::parse("7,4,9,5,11,17,23,2\n").unwrap();
+        assert_eq!(squid.part1(), Answer::Integer(-1));
+        assert_eq!(squid.part2(), Answer::Integer(-1));
+    }
+
+    #[test]
+    fn test_string_to_vec_trailing_newline() {
+        let s = get_string_board() + "\n";
+        let s_boards = GiantSquid::string_to_vector(s).unwrap();
+        assert_eq!(s_boards.len(), 3);
+
+        let s = get_string_board().replace('\n', "\r\n") + "\r\n";
+        assert_eq!(GiantSquid::string_to_vector(s).unwrap(), s_boards);
+    }
+
+    #[test]
+    fn test_string_to_vec_malformed() {
+        let s = get_string_board().replace("9 18 13 17  5", "9 18 1x 17  5");
+        let err = GiantSquid::string_to_vector(s).unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (4, 10, 6));
+        assert_eq!(err.text, "1x");
+
+        let s = get_string_board().replace("9 18 13 17  5", "9 18 13 17");
+        let err = GiantSquid::string_to_vector(s).unwrap_err();
+        assert_eq!((err.line, err.column), (10, 11));
+
+        let s = get_string_board().replace("\n19  8  7 25 23", "");
+        let err = GiantSquid::string_to_vector(s).unwrap_err();
+        assert_eq!(err.line, 13);
+
+        let s = get_string_board().replace("\n\n3 15", "\n3 15");
+        let err = GiantSquid::string_to_vector(s).unwrap_err();
+        assert_eq!((err.line, err.expected), (8, "a blank line between boards"));
+
+        let s = get_string_board().replace("9 18 13 17  5", "9 18 13 17  5 1");
+        let err = GiantSquid::from_reader(s.as_bytes()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 4 input, line 10, column 15: expected end of row, found '1'"
+        );
+
+        let err = GiantSquid::parse("7,4,x9\n").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 4 input, line 1, column 5: expected an integer, found 'x9'"
+        );
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let squid = GiantSquid::from_reader(get_string_board().as_bytes()).unwrap();
+        assert_eq!(squid.numbers.len(), 27);
+        assert_eq!(squid.boards.len(), 3);
+
+        let err = GiantSquid::from_reader("".as_bytes()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 4 input, line 1, column 1: expected the drawn numbers, found ''"
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let squid = get_board();
+        let (_, lines) = trace::capture(2021, 4, Level::Info, || {
+            squid.clone().find_first_winner_code();
+            squid.clone().find_last_winner_code()
+        });
+        assert_eq!(
+            lines,
+            vec![
+                "[2021 day 4 info] board 2 wins first, on draw 24",
+                "[2021 day 4 info] board 1 wins last, on draw 13, with 148 unmarked",
+            ]
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use core::fmt;
+use std::{error::Error, io::BufRead};
+
+use crate::{
+    d00_aoc::{for_each_line, query_arg, Answer, InputReader, ParseError, Solution},
+    grid::{Grid, Point},
+    parse,
+    trace::{self, Level},
+};
+
+#[derive(Clone, PartialEq)]
+pub struct Line {
+    p1: Point,
+    p2: Point,
+}
+
+impl fmt::Debug for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Line")
+            .field("p1", &self.p1)
+            .field("p2", &self.p2)
+            .finish()
+    }
+}
+
+pub struct HydroThermalVenture {
+    vents: Grid<i32>,
+    vents_diag: Grid<i32>,
+}
+
+impl InputReader<Line> for HydroThermalVenture {
+    fn string_to_vector(input_str: String) -> Result<Vec<Line>, ParseError> {
+        parse::lines(&input_str)
+            .map(|(n, f)| Self::parse_line(n, f))
+            .collect()
+    }
+
+    fn from_reader<R: BufRead>(reader: R) -> Result<Self, Box<dyn Error>> {
+        let mut htv = HydroThermalVenture::new(vec![]);
+        for_each_line(reader, |n, f| {
+            htv.add_line(&Self::parse_line(n, f)?);
+            Ok(())
+        })?;
+        Ok(htv)
+    }
+}
+
+impl Solution for HydroThermalVenture {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        Self::from_reader(input.as_bytes())
+    }
+
+    fn part1(&self) -> Answer {
+        self.overlaps().into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.overlaps_diag().into()
+    }
+
+    fn queries(&self) -> Vec<(&'static str, &'static str)> {
+        vec![(
+            "vents <X> <Y>",
+            "lines covering the point, straight ones and all of them",
+        )]
+    }
+
+    fn query(&self, name: &str, args: &[&str]) -> Option<Result<String, Box<dyn Error>>> {
+        if name != "vents" {
+            return None;
+        }
+        let point =
+            query_arg(args, 0, "X").and_then(|x| Ok(Point::new(x, query_arg(args, 1, "Y")?)));
+        Some(point.map(|p| {
+            format!(
+                "{} straight, {} with diagonals",
+                self.vents[p], self.vents_diag[p]
+            )
+        }))
+    }
+}
+
+impl HydroThermalVenture {
+    fn parse_line(line_number: usize, f: &str) -> Result<Line, ParseError> {
+        let (p1, p2) = parse::segment(5, line_number, f)?;
+        Ok(Line { p1, p2 })
+    }
+
+    pub fn new(lines: Vec<Line>) -> HydroThermalVenture {
+        let mut htv = HydroThermalVenture {
+            vents: Grid::sparse(),
+            vents_diag: Grid::sparse(),
+        };
+        for i in lines {
+            htv.add_line(&i);
+        }
+        htv
+    }
+
+    fn add_line(&mut self, line: &Line) {
+        let straight = line.p1.x == line.p2.x || line.p1.y == line.p2.y;
+        let mut count = 0;
+        if let Some(points) = line.p1.segment(line.p2) {
+            for p in points {
+                if straight {
+                    self.vents[p] += 1;
+                }
+                self.vents_diag[p] += 1;
+                count += 1;
+            }
+        }
+        trace::log(2021, 5, Level::Debug, || {
+            format!("{} -> {} covers {} points", line.p1, line.p2, count)
+        });
+    }
+
+    pub fn overlaps(&self) -> i32 {
+        let overlaps = self.vents.iter().filter(|f| *f.1 > 1).count() as i32;
+        trace::log(2021, 5, Level::Info, || {
+            format!("{} straight vents overlap", overlaps)
+        });
+        overlaps
+    }
+
+    pub fn overlaps_diag(&self) -> i32 {
+        let overlaps = self.vents_diag.iter().filter(|f| *f.1 > 1).count() as i32;
+        trace::log(2021, 5, Level::Info, || {
+            format!("{} vents overlap", overlaps)
+        });
+        overlaps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        d00_aoc::{InputReader, Solution},
+        grid::Point,
+        trace::{self, Level},
+    };
+
+    use super::{HydroThermalVenture, Line};
+
+    fn line(x1: i64, y1: i64, x2: i64, y2: i64) -> Line {
+        Line {
+            p1: Point::new(x1, y1),
+            p2: Point::new(x2, y2),
+        }
+    }
+
+    fn get_lines() -> Vec<Line> {
+        [
+            line(0, 9, 5, 9),
+            line(8, 0, 0, 8),
+            line(9, 4, 3, 4),
+            line(2, 2, 2, 1),
+            line(7, 0, 7, 4),
+            line(6, 4, 2, 0),
+            line(0, 9, 2, 9),
+            line(3, 4, 1, 4),
+            line(0, 0, 8, 8),
+            line(5, 5, 8, 2),
+        ]
+        .to_vec()
+    }
+
+    #[test]
+    fn test_overlap() {
+        let lines = get_lines();
+        let htv = HydroThermalVenture::new(lines);
+        assert_eq!(htv.overlaps(), 5);
+        assert_eq!(htv.overlaps_diag(), 12);
+    }
+
+    #[test]
+    fn test_hydrothermal_venture() {
+        let diagram = "\
+.......1..\n\
+..1....1..\n\
+..1....1..\n\
+.......1..\n\
+.112111211\n\
+..........\n\
+..........\n\
+..........\n\
+..........\n\
+222111....\n";
+        let htv = HydroThermalVenture::new(get_lines());
+        assert_eq!(htv.vents.to_string(), diagram);
+    }
+
+    #[test]
+    fn test_hydrothermal_venture_diagonal() {
+        let diagram = "\
+1.1....11.\n\
+.111...2..\n\
+..2.1.111.\n\
+...1.2.2..\n\
+.112313211\n\
+...1.2....\n\
+..1...1...\n\
+.1.....1..\n\
+1.......1.\n\
+222111....\n";
+        let htv = HydroThermalVenture::new(get_lines());
+        assert_eq!(htv.vents_diag.to_string(), diagram);
+    }
+
+    #[test]
+    fn test_string_to_vec() {
+        let input = r#"0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2"#;
+
+        let b = get_lines();
+        let lines = HydroThermalVenture::string_to_vector(input.to_string()).unwrap();
+        let equal_count = lines.iter().zip(&b).filter(|&(a, b)| a == b).count();
+
+        assert_eq!(lines.len(), equal_count);
+        assert_eq!(b.len(), equal_count);
+    }
+
+    #[test]
+    fn test_string_to_vec_trailing_newline() {
+        let lines = HydroThermalVenture::string_to_vector("0,9 -> 5,9\n".to_string()).unwrap();
+        assert_eq!(lines, get_lines()[..1].to_vec());
+    }
+
+    #[test]
+    fn test_string_to_vec_malformed() {
+        let input = "0,9 -> 5,9\n8,0 -> 0,y";
+        let err = HydroThermalVenture::string_to_vector(input.to_string()).unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (5, 2, 10));
+        assert_eq!(err.text, "y");
+
+        let input = "0,9 -> 5,9\n8,0 => 0,8";
+        let err = HydroThermalVenture::string_to_vector(input.to_string()).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+
+        let input = "0,9 -> 59";
+        let err = HydroThermalVenture::string_to_vector(input.to_string()).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 8, "59"));
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let input = "0,9 -> 5,9\n8,0 -> 0,8\n9,4 -> 3,4\n2,2 -> 2,1\n7,0 -> 7,4\n6,4 -> 2,0\n0,9 -> 2,9\n3,4 -> 1,4\n0,0 -> 8,8\n5,5 -> 8,2\n";
+        let htv = HydroThermalVenture::from_reader(input.as_bytes()).unwrap();
+        assert_eq!(htv.overlaps(), 5);
+        assert_eq!(htv.overlaps_diag(), 12);
+
+        let err = HydroThermalVenture::from_reader("0,9 -> 5,9\n8,0 -> 0".as_bytes())
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "day 5 input, line 2, column 8: expected a point x,y, found '0'"
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let (htv, lines) = trace::capture(2021, 5, Level::Debug, || {
+            HydroThermalVenture::new(get_lines()[..2].to_vec())
+        });
+        assert_eq!(
+            lines,
+            vec![
+                "[2021 day 5 debug] 0,9 -> 5,9 covers 6 points",
+                "[2021 day 5 debug] 8,0 -> 0,8 covers 9 points",
+            ]
+        );
+        let (_, lines) = trace::capture(2021, 5, Level::Info, || htv.overlaps_diag());
+        assert_eq!(lines, vec!["[2021 day 5 info] 0 vents overlap"]);
+    }
+
+    #[test]
+    fn test_query() {
+        let htv = HydroThermalVenture::new(get_lines());
+        assert_eq!(
+            htv.query("vents", &["4", "4"]).unwrap().unwrap(),
+            "1 straight, 3 with diagonals"
+        );
+        assert_eq!(
+            htv.query("vents", &["-1", "4"]).unwrap().unwrap(),
+            "0 straight, 0 with diagonals"
+        );
+        let err = htv.query("vents", &["7"]).unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "missing argument <Y>");
+    }
+}
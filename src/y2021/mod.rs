@@ -0,0 +1,5 @@
+pub mod d01_sonar_deep;
+pub mod d02_dive;
+pub mod d03_binary_diagnostic;
+pub mod d04_giant_squid;
+pub mod d05_hydrothermal_veture;